@@ -0,0 +1,161 @@
+use crate::{
+    docker::{
+        retention::{self, RetentionPolicy},
+        runtime, KeepList,
+    },
+    dry_run, home_dir,
+    size::format_size,
+    App,
+};
+use std::{path::Path, thread, time::Duration};
+
+const USAGE: &str = "\
+usage: macmon                       start the monitor
+       macmon docker-retention [--runtime NAME] [--keep-newest N] [--keep-tag PATTERN]...
+                               [--keep-dangling] [--dry-run]
+       macmon dry-run [--build-cache-hours N] [--nix-keep-days N]
+
+docker-retention removes all but the newest N tags (default 3) of each local repository,
+keeping tags matching any --keep-tag pattern (default: latest, stable) and removing dangling
+images unless --keep-dangling is given. Images used by containers or matching
+~/.config/macmon/docker-keep are never removed. --runtime picks docker, podman or nerdctl
+when more than one is installed; the first one found is used otherwise.
+
+dry-run scans like the monitor does and prints every path, image and container resource each
+cleanup action would remove, with the bytes it would free, without removing anything.";
+
+/// Runs a non-interactive command. Errors are printed by the caller, which exits non-zero.
+pub fn run(args: &[String]) -> Result<(), String> {
+    match args[0].as_str() {
+        "docker-retention" => docker_retention(&args[1..]),
+        "dry-run" => dry_run(&args[1..]),
+        "-h" | "--help" => {
+            println!("{USAGE}");
+            Ok(())
+        },
+        other => Err(format!("unknown command `{other}`\n\n{USAGE}")),
+    }
+}
+
+fn docker_retention(args: &[String]) -> Result<(), String> {
+    let mut policy = RetentionPolicy::default();
+    let mut keep_tags = Vec::new();
+    let mut dry_run = false;
+    let mut runtime_name = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--keep-newest" => {
+                policy.keep_newest = args
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .ok_or("--keep-newest needs a number")?;
+            },
+            "--keep-tag" => keep_tags.push(args.next().ok_or("--keep-tag needs a pattern")?.clone()),
+            "--runtime" => runtime_name = Some(args.next().ok_or("--runtime needs a name")?.to_lowercase()),
+            "--keep-dangling" => policy.remove_dangling = false,
+            "--dry-run" => dry_run = true,
+            other => return Err(format!("unknown option `{other}`\n\n{USAGE}")),
+        }
+    }
+    if !keep_tags.is_empty() {
+        policy.keep_tags = keep_tags;
+    }
+
+    let home = home_dir();
+    let home = Path::new(&home);
+    let runtimes = runtime::detect(home);
+    let runtime = match &runtime_name {
+        Some(name) => runtimes
+            .into_iter()
+            .find(|r| r.name.to_lowercase() == *name)
+            .ok_or_else(|| format!("{name} was not found"))?,
+        None => runtimes
+            .into_iter()
+            .next()
+            .ok_or("no Docker, Podman or nerdctl runtime found")?,
+    };
+    let usage = runtime.disk_usage().map_err(|err| err.to_string())?;
+    let plan = retention::plan(&usage, &policy, &KeepList::load(home));
+
+    if plan.is_empty() {
+        println!("Nothing to remove");
+        return Ok(());
+    }
+
+    println!(
+        "{}: {} {} tags and {} dangling images ({}):",
+        runtime.name,
+        if dry_run { "would remove" } else { "removing" },
+        plan.tags.len(),
+        plan.dangling.len(),
+        format_size(plan.bytes)
+    );
+    for name in plan.tags.iter().chain(&plan.dangling) {
+        println!("  {name}");
+    }
+
+    if dry_run {
+        return Ok(());
+    }
+
+    let failures = retention::apply(&runtime, &plan);
+    for (name, err) in &failures {
+        eprintln!("failed to remove {name}: {err}");
+    }
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("{} of {} removals failed", failures.len(), plan.tags.len() + plan.dangling.len()))
+    }
+}
+
+fn dry_run(args: &[String]) -> Result<(), String> {
+    let mut app = App::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--build-cache-hours" => {
+                app.build_cache_hours = args
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .ok_or("--build-cache-hours needs a number")?;
+            },
+            "--nix-keep-days" => {
+                app.nix_keep_days = args
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .ok_or("--nix-keep-days needs a number")?;
+            },
+            other => return Err(format!("unknown option `{other}`\n\n{USAGE}")),
+        }
+    }
+
+    while app.issues.lock().unwrap().scanning {
+        thread::sleep(Duration::from_millis(100));
+    }
+
+    let rows = crate::cleanup_rows(&app.issues.lock().unwrap());
+    let plans: Vec<_> = rows
+        .into_iter()
+        .filter_map(|row| dry_run::plan(&app, row))
+        .filter(|plan| !plan.is_empty())
+        .collect();
+
+    if plans.is_empty() {
+        println!("Nothing to remove");
+    }
+    for plan in &plans {
+        println!("{}: would free {}", plan.action, format_size(plan.bytes));
+        for (name, size) in &plan.items {
+            if *size > 0 {
+                println!("  {name} ({})", format_size(*size));
+            } else {
+                println!("  {name}");
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,125 @@
+use super::{file_name, subdirs, version_key, Finding};
+use crate::size::format_size;
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+pub fn scan(home: &Path) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    if let Some(sdk) = sdk_root(home) {
+        for api in subdirs(&sdk.join("system-images")) {
+            for tag in subdirs(&api) {
+                for abi in subdirs(&tag) {
+                    let label = format!(
+                        "System image: {} {} {}",
+                        file_name(&api),
+                        file_name(&tag),
+                        file_name(&abi)
+                    );
+                    findings.push(Finding::from_path(label, &abi));
+                }
+            }
+        }
+
+        for version in outdated_versions(&sdk.join("build-tools")) {
+            let label = format!("Build tools {} (outdated)", file_name(&version));
+            findings.push(Finding::from_path(label, &version));
+        }
+
+        for platform in outdated_versions(&sdk.join("platforms")) {
+            let label = format!("Platform {} (outdated)", file_name(&platform));
+            findings.push(Finding::from_path(label, &platform));
+        }
+    }
+
+    let android_home = home.join(".android");
+    let avd_home = env::var_os("ANDROID_AVD_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| android_home.join("avd"));
+
+    for avd in subdirs(&avd_home) {
+        let name = file_name(&avd);
+        let Some(name) = name.strip_suffix(".avd") else {
+            continue;
+        };
+        let userdata = avd
+            .join("userdata-qemu.img")
+            .metadata()
+            .map(|m| m.len())
+            .unwrap_or(0);
+        let label = format!("Emulator: {name} (userdata {})", format_size(userdata));
+        // The emulator and Android Studio list AVDs by their `<name>.ini`, which points at
+        // the directory, so it goes too.
+        let ini = avd_home.join(format!("{name}.ini"));
+        findings.push(Finding::from_path(label, &avd).with_companion(ini));
+    }
+
+    let cache = android_home.join("cache");
+    if cache.is_dir() {
+        findings.push(Finding::from_path("Android cache", &cache));
+    }
+
+    findings
+}
+
+fn sdk_root(home: &Path) -> Option<PathBuf> {
+    let candidates = [
+        env::var_os("ANDROID_HOME").map(PathBuf::from),
+        env::var_os("ANDROID_SDK_ROOT").map(PathBuf::from),
+        Some(home.join("Library/Android/sdk")),
+        Some(home.join("Android/Sdk")),
+    ];
+
+    candidates.into_iter().flatten().find(|p| p.is_dir())
+}
+
+/// Every version directory under `path` except the newest one. Preview platforms named by
+/// codename, such as `android-UpsideDownCake`, carry no number to rank them and are left alone.
+fn outdated_versions(path: &Path) -> Vec<PathBuf> {
+    let mut versions: Vec<PathBuf> = subdirs(path)
+        .into_iter()
+        .filter(|v| !version_key(&file_name(v)).is_empty())
+        .collect();
+    versions.sort_by_key(|v| version_key(&file_name(v)));
+    versions.pop();
+    versions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trash::Deletion;
+    use std::fs;
+
+    #[test]
+    fn removes_an_emulator_with_its_ini() {
+        let home = env::temp_dir().join(format!("macmon-avd-{}", std::process::id()));
+        let avd_home = home.join(".android/avd");
+        fs::create_dir_all(avd_home.join("Pixel_7.avd")).unwrap();
+        fs::write(avd_home.join("Pixel_7.avd/userdata-qemu.img"), [0; 2048]).unwrap();
+        fs::write(avd_home.join("Pixel_7.ini"), "path=Pixel_7.avd\n").unwrap();
+
+        let emulator = scan(&home).into_iter().find(|f| f.label.starts_with("Emulator")).unwrap();
+        assert_eq!(emulator.label, "Emulator: Pixel_7 (userdata 2.0KB)");
+        emulator.remove(Deletion::Permanent).unwrap();
+        let left = fs::read_dir(&avd_home).unwrap().count();
+        fs::remove_dir_all(&home).unwrap();
+
+        assert_eq!(left, 0);
+    }
+
+    #[test]
+    fn keeps_the_newest_and_codename_platforms() {
+        let platforms = env::temp_dir().join(format!("macmon-android-{}", std::process::id()));
+        for name in ["android-33", "android-34", "android-UpsideDownCake"] {
+            fs::create_dir_all(platforms.join(name)).unwrap();
+        }
+
+        let outdated = outdated_versions(&platforms);
+        fs::remove_dir_all(&platforms).unwrap();
+
+        assert_eq!(outdated, vec![platforms.join("android-33")]);
+    }
+}
@@ -0,0 +1,189 @@
+use super::{file_name, subdirs, Finding};
+use std::{collections::HashSet, path::Path};
+
+const CACHE_DIR_NAMES: [&str; 5] = ["Cache", "Code Cache", "GPUCache", "DawnCache", "DawnGraphiteCache"];
+
+struct KnownApp {
+    name: &'static str,
+    /// Lowercase prefixes of the app's process names.
+    processes: &'static [&'static str],
+    /// Data directories searched, along with their profile subdirectories, for cache folders.
+    data_dirs: &'static [&'static str],
+    /// Directories that are caches in their entirety.
+    cache_dirs: &'static [&'static str],
+}
+
+const KNOWN_APPS: [KnownApp; 9] = [
+    KnownApp {
+        name: "Slack",
+        processes: &["slack"],
+        data_dirs: &["Library/Application Support/Slack", ".config/Slack"],
+        cache_dirs: &["Library/Caches/com.tinyspeck.slackmacgap"],
+    },
+    KnownApp {
+        name: "Discord",
+        processes: &["discord"],
+        data_dirs: &["Library/Application Support/discord", ".config/discord"],
+        cache_dirs: &[],
+    },
+    KnownApp {
+        name: "Teams",
+        processes: &["teams", "microsoft teams", "msteams"],
+        data_dirs: &["Library/Application Support/Microsoft/Teams", ".config/Microsoft/Microsoft Teams"],
+        cache_dirs: &["Library/Containers/com.microsoft.teams2/Data/Library/Caches"],
+    },
+    KnownApp {
+        name: "Spotify",
+        processes: &["spotify"],
+        data_dirs: &[],
+        cache_dirs: &[
+            "Library/Caches/com.spotify.client",
+            "Library/Application Support/Spotify/PersistentCache",
+            ".cache/spotify",
+        ],
+    },
+    KnownApp {
+        name: "Chrome",
+        processes: &["google chrome", "chrome"],
+        data_dirs: &["Library/Application Support/Google/Chrome", ".config/google-chrome"],
+        cache_dirs: &["Library/Caches/Google/Chrome", ".cache/google-chrome"],
+    },
+    KnownApp {
+        name: "Chromium",
+        processes: &["chromium"],
+        data_dirs: &["Library/Application Support/Chromium", ".config/chromium"],
+        cache_dirs: &["Library/Caches/Chromium", ".cache/chromium"],
+    },
+    KnownApp {
+        name: "Brave",
+        processes: &["brave"],
+        data_dirs: &[
+            "Library/Application Support/BraveSoftware/Brave-Browser",
+            ".config/BraveSoftware/Brave-Browser",
+        ],
+        cache_dirs: &["Library/Caches/BraveSoftware", ".cache/BraveSoftware"],
+    },
+    KnownApp {
+        name: "Edge",
+        processes: &["microsoft edge", "msedge"],
+        data_dirs: &["Library/Application Support/Microsoft Edge", ".config/microsoft-edge"],
+        cache_dirs: &["Library/Caches/Microsoft Edge", ".cache/microsoft-edge"],
+    },
+    KnownApp {
+        name: "Firefox",
+        processes: &["firefox"],
+        data_dirs: &[],
+        cache_dirs: &["Library/Caches/Firefox", ".cache/mozilla/firefox"],
+    },
+];
+
+#[derive(Clone)]
+pub struct AppCache {
+    pub app: &'static str,
+    pub processes: &'static [&'static str],
+    pub caches: Vec<Finding>,
+}
+
+impl AppCache {
+    pub fn size(&self) -> u64 {
+        self.caches.iter().map(|c| c.size).sum()
+    }
+
+    /// Whether any of the lowercased process names in `running` belongs to this app.
+    pub fn is_running(&self, running: &HashSet<String>) -> bool {
+        running
+            .iter()
+            .any(|name| self.processes.iter().any(|prefix| name.starts_with(prefix)))
+    }
+}
+
+pub fn scan(home: &Path) -> Vec<AppCache> {
+    let mut apps: Vec<AppCache> = KNOWN_APPS
+        .iter()
+        .map(|known| {
+            let mut caches = Vec::new();
+
+            for data_dir in known.data_dirs {
+                let data_dir = home.join(data_dir);
+                let mut candidates = vec![data_dir.clone()];
+                candidates.extend(subdirs(&data_dir));
+
+                for dir in candidates {
+                    for cache_name in CACHE_DIR_NAMES {
+                        let cache = dir.join(cache_name);
+                        if cache.is_dir() {
+                            let label = format!("{}: {} {cache_name}", known.name, file_name(&dir));
+                            caches.push(Finding::from_path(label, &cache));
+                        }
+                    }
+                }
+            }
+
+            for cache_dir in known.cache_dirs {
+                let cache = home.join(cache_dir);
+                if cache.is_dir() {
+                    caches.push(Finding::from_path(format!("{}: {}", known.name, file_name(&cache)), &cache));
+                }
+            }
+
+            AppCache {
+                app: known.name,
+                processes: known.processes,
+                caches,
+            }
+        })
+        .filter(|app| app.size() > 0)
+        .collect();
+
+    apps.sort_by_key(|app| std::cmp::Reverse(app.size()));
+    apps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, fs};
+
+    #[test]
+    fn finds_caches_and_tells_which_apps_are_running() {
+        let home = env::temp_dir().join(format!("macmon-app-caches-{}", std::process::id()));
+        for cache in [
+            "Library/Application Support/Slack/Cache",
+            "Library/Application Support/Google/Chrome/Default/Code Cache",
+            "Library/Application Support/Google/Chrome/Profile 1/GPUCache",
+            "Library/Containers/com.microsoft.teams2/Data/Library/Caches",
+            "Library/Caches/Firefox",
+        ] {
+            fs::create_dir_all(home.join(cache)).unwrap();
+            fs::write(home.join(cache).join("data_0"), [0; 64]).unwrap();
+        }
+        fs::create_dir_all(home.join("Library/Application Support/discord/Cache")).unwrap();
+
+        let caches = scan(&home);
+        fs::remove_dir_all(&home).unwrap();
+
+        let mut found: Vec<(&str, Vec<String>)> = caches
+            .iter()
+            .map(|cache| (cache.app, cache.caches.iter().map(|f| f.label.clone()).collect()))
+            .collect();
+        found.sort();
+        assert_eq!(
+            found,
+            [
+                ("Chrome", vec!["Chrome: Default Code Cache".to_string(), "Chrome: Profile 1 GPUCache".to_string()]),
+                ("Firefox", vec!["Firefox: Firefox".to_string()]),
+                ("Slack", vec!["Slack: Slack Cache".to_string()]),
+                ("Teams", vec!["Teams: Caches".to_string()]),
+            ]
+        );
+
+        let running: HashSet<String> = ["msteams", "google chrome helper (renderer)", "launchd"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let mut running_apps: Vec<&str> =
+            caches.iter().filter(|cache| cache.is_running(&running)).map(|cache| cache.app).collect();
+        running_apps.sort();
+        assert_eq!(running_apps, ["Chrome", "Teams"]);
+    }
+}
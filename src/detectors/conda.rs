@@ -0,0 +1,170 @@
+use super::{file_name, is_installed, Finding};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+    time::{Duration, SystemTime},
+};
+
+const UNUSED_AFTER: Duration = Duration::from_secs(90 * 86_400);
+
+pub fn scan(home: &Path) -> Vec<Finding> {
+    let prefixes = environment_prefixes(home);
+    let mut findings = Vec::new();
+    let mut pkgs_dirs = Vec::new();
+
+    for prefix in &prefixes {
+        // The base environment holds the installation itself and cannot be removed.
+        if prefix.join("envs").is_dir() || prefix.join("condabin").is_dir() {
+            pkgs_dirs.push(prefix.join("pkgs"));
+            continue;
+        }
+        if !prefix.join("conda-meta").is_dir() {
+            continue;
+        }
+
+        let name = file_name(prefix);
+        let mut finding = Finding::from_path(format!("Conda env: {name}"), prefix);
+        if is_unused(finding.last_used) {
+            finding.label = format!("Conda env: {name} (unused for months)");
+        }
+        findings.push(finding.with_command(
+            vec![
+                conda_program(&prefixes),
+                "env".to_string(),
+                "remove".to_string(),
+                "-y".to_string(),
+                "-p".to_string(),
+                prefix.display().to_string(),
+            ],
+            false,
+        ));
+    }
+
+    // `conda clean --all` empties every package cache at once, so it is offered once, on the
+    // cache of the base installation when there is one.
+    pkgs_dirs.push(home.join(".conda/pkgs"));
+    if let Some(pkgs) = pkgs_dirs.iter().find(|p| p.is_dir()) {
+        let finding = Finding::from_path(format!("Conda package cache: {}", pkgs.display()), pkgs)
+            .with_command(
+                vec![
+                    conda_program(&prefixes),
+                    "clean".to_string(),
+                    "--all".to_string(),
+                    "-y".to_string(),
+                ],
+                false,
+            );
+        findings.push(finding);
+    }
+
+    findings
+}
+
+/// Environment prefixes from `~/.conda/environments.txt`, falling back to `conda env list`.
+fn environment_prefixes(home: &Path) -> Vec<PathBuf> {
+    let listed: Vec<String> = match fs::read_to_string(home.join(".conda/environments.txt")) {
+        // One prefix per line, which may contain spaces.
+        Ok(contents) => listed_lines(&contents).map(String::from).collect(),
+        // `name  *  /prefix` columns, with the prefix last.
+        Err(_) => conda_env_list()
+            .as_deref()
+            .map(|output| {
+                listed_lines(output)
+                    .filter_map(|line| line.split_whitespace().last())
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default(),
+    };
+
+    let mut prefixes: Vec<PathBuf> = listed
+        .into_iter()
+        .map(PathBuf::from)
+        .filter(|prefix| prefix.is_dir())
+        .collect();
+    prefixes.sort();
+    prefixes.dedup();
+    prefixes
+}
+
+fn listed_lines(contents: &str) -> impl Iterator<Item = &str> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+}
+
+fn conda_env_list() -> Option<String> {
+    if !is_installed("conda") {
+        return None;
+    }
+    let output = Command::new("conda").args(["env", "list"]).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// `conda` from `PATH`, or the binary of the base installation when it is not on `PATH`.
+fn conda_program(prefixes: &[PathBuf]) -> String {
+    if is_installed("conda") {
+        return "conda".to_string();
+    }
+    prefixes
+        .iter()
+        .map(|prefix| prefix.join("bin/conda"))
+        .find(|bin| bin.is_file())
+        .map(|bin| bin.display().to_string())
+        .unwrap_or_else(|| "conda".to_string())
+}
+
+fn is_unused(last_used: Option<SystemTime>) -> bool {
+    last_used
+        .and_then(|t| t.elapsed().ok())
+        .is_some_and(|elapsed| elapsed > UNUSED_AFTER)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detectors::Removal;
+    use std::env;
+
+    fn args(finding: &Finding) -> Vec<&str> {
+        match &finding.removal {
+            Removal::Command { args, .. } => args[1..].iter().map(String::as_str).collect(),
+            Removal::DeletePath => Vec::new(),
+        }
+    }
+
+    #[test]
+    fn offers_environments_and_one_package_cache_clean() {
+        let home = env::temp_dir().join(format!("macmon-conda-{}", std::process::id()));
+        let base = home.join("mini conda3");
+        let data_science = base.join("envs/data science");
+        for dir in [
+            base.join("conda-meta"),
+            base.join("pkgs"),
+            data_science.join("conda-meta"),
+            home.join(".conda/pkgs"),
+        ] {
+            fs::create_dir_all(dir).unwrap();
+        }
+        fs::write(
+            home.join(".conda/environments.txt"),
+            format!("{}\n\n# removed later\n  {}  \n", base.display(), data_science.display()),
+        )
+        .unwrap();
+
+        let findings = scan(&home);
+        fs::remove_dir_all(&home).unwrap();
+
+        let labels: Vec<&str> = findings.iter().map(|f| f.label.as_str()).collect();
+        let cache_label = format!("Conda package cache: {}", base.join("pkgs").display());
+        assert_eq!(labels, ["Conda env: data science", cache_label.as_str()]);
+        let prefix = data_science.display().to_string();
+        assert_eq!(args(&findings[0]), ["env", "remove", "-y", "-p", prefix.as_str()]);
+        assert_eq!(args(&findings[1]), ["clean", "--all", "-y"]);
+    }
+}
@@ -0,0 +1,174 @@
+use super::{calculate_dir_size, is_installed, ToolOutput};
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+#[derive(Clone)]
+pub struct HomebrewReport {
+    /// Paths `brew cleanup` would remove, with their size in bytes.
+    pub removable: Vec<(PathBuf, u64)>,
+    pub reclaimable: u64,
+    /// Installed versions of a formula that `brew cleanup` would remove, as
+    /// `(formula, version, size)`.
+    pub old_kegs: Vec<(String, String, u64)>,
+    pub cache_size: u64,
+}
+
+pub fn scan() -> Option<HomebrewReport> {
+    if !is_installed("brew") {
+        return None;
+    }
+
+    let preview = run(&["cleanup", "-n", "-s"])?;
+    let (removable, reclaimable) = parse_cleanup_preview(&preview);
+    let old_kegs = old_kegs(&removable);
+    let cache_size = run(&["--cache"])
+        .map(|out| calculate_dir_size(&PathBuf::from(out.trim())))
+        .unwrap_or(0);
+
+    Some(HomebrewReport {
+        removable,
+        reclaimable,
+        old_kegs,
+        cache_size,
+    })
+}
+
+/// Runs `brew cleanup -s` and returns the bytes it reports having freed.
+pub fn cleanup() -> Result<ToolOutput, String> {
+    let output = Command::new("brew")
+        .args(["cleanup", "-s"])
+        .output()
+        .map_err(|err| format!("could not run brew: {err}"))?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(ToolOutput {
+        freed: freed_total(&stdout, "has freed").unwrap_or(0),
+        stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+    })
+}
+
+/// Parses `brew cleanup -n` output into the paths it would remove and the total it would free.
+pub fn parse_cleanup_preview(output: &str) -> (Vec<(PathBuf, u64)>, u64) {
+    let removable: Vec<(PathBuf, u64)> = output
+        .lines()
+        .filter_map(|line| line.strip_prefix("Would remove: "))
+        .map(|rest| match rest.rsplit_once(" (") {
+            Some((path, details)) => {
+                let size = details
+                    .trim_end_matches(')')
+                    .rsplit(", ")
+                    .next()
+                    .and_then(parse_size)
+                    .unwrap_or(0);
+                (PathBuf::from(path), size)
+            },
+            None => (PathBuf::from(rest), 0),
+        })
+        .collect();
+
+    let total = freed_total(output, "would free")
+        .unwrap_or_else(|| removable.iter().map(|(_, size)| size).sum());
+    (removable, total)
+}
+
+/// The entries of a cleanup preview that are kegs, `<prefix>/Cellar/<formula>/<version>`.
+fn old_kegs(removable: &[(PathBuf, u64)]) -> Vec<(String, String, u64)> {
+    removable
+        .iter()
+        .filter_map(|(path, size)| {
+            let formula = path.parent()?;
+            if formula.parent()?.file_name()? != "Cellar" {
+                return None;
+            }
+            let name = |path: &Path| path.file_name().map(|name| name.to_string_lossy().to_string());
+            Some((name(formula)?, name(path)?, *size))
+        })
+        .collect()
+}
+
+/// The size in "This operation {verb} approximately 1.2GB of disk space".
+fn freed_total(output: &str, verb: &str) -> Option<u64> {
+    let line = output.lines().find(|line| line.contains(verb))?;
+    let after = line.split("approximately ").nth(1)?;
+    parse_size(after.split_whitespace().next()?)
+}
+
+/// Parses Homebrew's human readable sizes such as `512B`, `3.4KB` or `1.2GB`.
+pub fn parse_size(text: &str) -> Option<u64> {
+    let text = text.trim();
+    let split = text.find(|c: char| !c.is_ascii_digit() && c != '.')?;
+    let (number, unit) = text.split_at(split);
+    let number: f64 = number.parse().ok()?;
+    let multiplier = match unit.trim() {
+        "B" => 1.0,
+        "KB" => 1024.0,
+        "MB" => 1024.0 * 1024.0,
+        "GB" => 1024.0 * 1024.0 * 1024.0,
+        "TB" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    Some((number * multiplier) as u64)
+}
+
+fn run(args: &[&str]) -> Option<String> {
+    let output = Command::new("brew").args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PREVIEW: &str = "\
+Would remove: /opt/homebrew/Cellar/openssl@3/3.1.1 (6,495 files, 28.4MB)
+Would remove: /opt/homebrew/Cellar/python@3.11/3.11.4_1 (3,305 files, 61.3MB)
+Would remove: /Users/me/Library/Caches/Homebrew/wget--1.21.4.arm64_ventura.bottle.tar.gz (1.5MB)
+Would remove: /Users/me/Library/Caches/Homebrew/Cask/firefox--115.0.dmg (131.7MB)
+Would remove: /Users/me/Library/Logs/Homebrew/wget (4 files, 12KB)
+==> This operation would free approximately 223.0MB of disk space.
+";
+
+    #[test]
+    fn parses_the_cleanup_preview() {
+        let (removable, total) = parse_cleanup_preview(PREVIEW);
+
+        assert_eq!(removable.len(), 5);
+        assert_eq!(removable[0], (PathBuf::from("/opt/homebrew/Cellar/openssl@3/3.1.1"), 29_779_558));
+        assert_eq!(
+            removable[3],
+            (PathBuf::from("/Users/me/Library/Caches/Homebrew/Cask/firefox--115.0.dmg"), 138_097_459)
+        );
+        assert_eq!(removable[4].1, 12_288);
+        assert_eq!(total, 233_832_448);
+    }
+
+    #[test]
+    fn picks_old_kegs_out_of_the_preview() {
+        let (removable, _) = parse_cleanup_preview(PREVIEW);
+        assert_eq!(
+            old_kegs(&removable),
+            [
+                ("openssl@3".to_string(), "3.1.1".to_string(), 29_779_558),
+                ("python@3.11".to_string(), "3.11.4_1".to_string(), 64_277_708),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_homebrew_sizes() {
+        assert_eq!(parse_size("512B"), Some(512));
+        assert_eq!(parse_size("3.4KB"), Some(3481));
+        assert_eq!(parse_size("1.2GB"), Some(1_288_490_188));
+        assert_eq!(parse_size("12"), None);
+        assert_eq!(parse_size("4PB"), None);
+    }
+}
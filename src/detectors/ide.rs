@@ -0,0 +1,267 @@
+use super::{file_name, subdirs, version_key, Finding};
+use serde_json::Value;
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+use walkdir::WalkDir;
+
+pub fn scan(root: &Path, home: &Path) -> Vec<Finding> {
+    let mut findings = jetbrains(root, home);
+    findings.extend(vscode_workspace_storage(home));
+    findings.extend(vscode_extensions(home));
+    findings
+}
+
+/// Caches, logs and indexes per JetBrains product version. Versions no installed IDE uses
+/// are flagged, since an IDE only reads the directories of its own version.
+fn jetbrains(root: &Path, home: &Path) -> Vec<Finding> {
+    let roots = [
+        ("caches", home.join("Library/Caches/JetBrains")),
+        ("logs", home.join("Library/Logs/JetBrains")),
+        ("caches", home.join(".cache/JetBrains")),
+    ];
+    let installed = jetbrains_installations(root, home);
+
+    let mut findings = Vec::new();
+    for (kind, dir) in &roots {
+        for dir in subdirs(dir) {
+            let name = file_name(&dir);
+            if split_product_version(&name).is_none() {
+                continue;
+            }
+            let label = if installed.contains(&name) {
+                format!("JetBrains {kind}: {name}")
+            } else {
+                format!("JetBrains {kind}: {name} (not installed)")
+            };
+            findings.push(Finding::from_path(label, &dir));
+        }
+    }
+    findings
+}
+
+/// The `dataDirectoryName` of every installed JetBrains IDE, such as `IntelliJIdea2023.2`, read
+/// from the `product-info.json` each installation ships: app bundles in `/Applications` and
+/// `~/Applications`, Toolbox installs, and archives unpacked into `/opt` or
+/// `~/.local/share/JetBrains`.
+fn jetbrains_installations(root: &Path, home: &Path) -> HashSet<String> {
+    let mut candidates = Vec::new();
+    for applications in [root.join("Applications"), home.join("Applications")] {
+        for app in subdirs(&applications) {
+            candidates.push(app.join("Contents/Resources/product-info.json"));
+        }
+    }
+    for dir in subdirs(&root.join("opt")) {
+        candidates.push(dir.join("product-info.json"));
+    }
+    for installs in [
+        home.join("Library/Application Support/JetBrains/Toolbox/apps"),
+        home.join(".local/share/JetBrains"),
+    ] {
+        candidates.extend(
+            WalkDir::new(installs)
+                .max_depth(7)
+                .into_iter()
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_name() == "product-info.json")
+                .map(|e| e.into_path()),
+        );
+    }
+
+    candidates
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .filter_map(|json| {
+            let info: Value = serde_json::from_str(&json).ok()?;
+            info["dataDirectoryName"].as_str().map(String::from)
+        })
+        .collect()
+}
+
+/// VS Code workspace state whose workspace folder no longer exists.
+fn vscode_workspace_storage(home: &Path) -> Vec<Finding> {
+    let roots = [
+        home.join("Library/Application Support/Code/User/workspaceStorage"),
+        home.join(".config/Code/User/workspaceStorage"),
+    ];
+
+    let mut findings = Vec::new();
+    for root in &roots {
+        for dir in subdirs(root) {
+            let Some(workspace) = fs::read_to_string(dir.join("workspace.json"))
+                .ok()
+                .and_then(|json| serde_json::from_str::<Value>(&json).ok())
+            else {
+                continue;
+            };
+            let Some(uri) = workspace["folder"].as_str().or_else(|| workspace["workspace"].as_str()) else {
+                continue;
+            };
+            let Some(path) = uri.strip_prefix("file://") else {
+                continue;
+            };
+            let workspace = PathBuf::from(percent_decode(path));
+            if !workspace.exists() {
+                let label = format!("VS Code workspace state: {} (folder gone)", workspace.display());
+                findings.push(Finding::from_path(label, &dir));
+            }
+        }
+    }
+    findings
+}
+
+/// Every installed VS Code extension version except the newest per extension.
+fn vscode_extensions(home: &Path) -> Vec<Finding> {
+    let mut by_extension: HashMap<String, Vec<(Vec<u64>, String, PathBuf)>> = HashMap::new();
+
+    for dir in subdirs(&home.join(".vscode/extensions")) {
+        let name = file_name(&dir);
+        let Some((extension, version)) = split_extension_version(&name) else {
+            continue;
+        };
+        by_extension
+            .entry(extension)
+            .or_default()
+            .push((version_key(&version), version, dir));
+    }
+
+    let mut findings = Vec::new();
+    for (extension, mut versions) in by_extension {
+        versions.sort();
+        versions.pop();
+        for (_, version, dir) in versions {
+            let label = format!("VS Code extension: {extension} {version} (old version)");
+            findings.push(Finding::from_path(label, &dir));
+        }
+    }
+    findings
+}
+
+/// `IntelliJIdea2023.2` -> (`IntelliJIdea`, `2023.2`).
+fn split_product_version(name: &str) -> Option<(String, String)> {
+    let index = name.find(|c: char| c.is_ascii_digit())?;
+    if index == 0 {
+        return None;
+    }
+    Some((name[..index].to_string(), name[index..].to_string()))
+}
+
+/// `ms-python.python-2024.2.1-darwin-arm64` -> (`ms-python.python`, `2024.2.1-darwin-arm64`).
+fn split_extension_version(name: &str) -> Option<(String, String)> {
+    let index = name
+        .char_indices()
+        .find(|&(i, c)| c == '-' && name[i + 1..].starts_with(|d: char| d.is_ascii_digit()))
+        .map(|(i, _)| i)?;
+    Some((name[..index].to_string(), name[index + 1..].to_string()))
+}
+
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(hex) = input.get(i + 1..i + 3)
+            && let Ok(byte) = u8::from_str_radix(hex, 16)
+        {
+            decoded.push(byte);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn flags_jetbrains_versions_without_an_installation() {
+        let root = env::temp_dir().join(format!("macmon-ide-{}", std::process::id()));
+        let home = root.join("home");
+        let caches = home.join("Library/Caches/JetBrains");
+        for version in ["IntelliJIdea2023.1", "IntelliJIdea2024.1", "PyCharm2023.3"] {
+            fs::create_dir_all(caches.join(version)).unwrap();
+        }
+        let app = root.join("Applications/IntelliJ IDEA.app/Contents/Resources");
+        fs::create_dir_all(&app).unwrap();
+        fs::write(app.join("product-info.json"), r#"{"name": "IntelliJ IDEA", "dataDirectoryName": "IntelliJIdea2023.1"}"#)
+            .unwrap();
+        let toolbox = home.join(".local/share/JetBrains/Toolbox/apps/pycharm-professional");
+        fs::create_dir_all(&toolbox).unwrap();
+        fs::write(toolbox.join("product-info.json"), r#"{"dataDirectoryName": "PyCharm2023.3"}"#).unwrap();
+
+        let labels: Vec<String> = jetbrains(&root, &home).into_iter().map(|f| f.label).collect();
+
+        assert_eq!(
+            labels,
+            [
+                "JetBrains caches: IntelliJIdea2023.1",
+                "JetBrains caches: IntelliJIdea2024.1 (not installed)",
+                "JetBrains caches: PyCharm2023.3",
+            ]
+        );
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn splits_vscode_extension_directories() {
+        let split = |name| split_extension_version(name).unwrap();
+        assert_eq!(
+            split("ms-python.python-2024.2.1-darwin-arm64"),
+            ("ms-python.python".to_string(), "2024.2.1-darwin-arm64".to_string())
+        );
+        assert_eq!(
+            split("ms-vscode-remote.remote-ssh-0.110.1"),
+            ("ms-vscode-remote.remote-ssh".to_string(), "0.110.1".to_string())
+        );
+        assert_eq!(split("dbaeumer.vscode-eslint-3.0.10"), ("dbaeumer.vscode-eslint".to_string(), "3.0.10".to_string()));
+        assert_eq!(split_extension_version("extensions.json"), None);
+    }
+
+    #[test]
+    fn flags_workspace_state_of_folders_that_are_gone() {
+        let home = env::temp_dir().join(format!("macmon-vscode-{}", std::process::id()));
+        let storage = home.join(".config/Code/User/workspaceStorage");
+        let project = home.join("projects/kept");
+        fs::create_dir_all(&project).unwrap();
+        let workspaces = [
+            ("a1", format!(r#"{{"folder": "file://{}"}}"#, project.display())),
+            ("b2", r#"{"folder": "file:///gone/say \"folder\": \"file:///tmp\""}"#.to_string()),
+            ("c3", r#"{"workspace": "file:///gone/app.code-workspace"}"#.to_string()),
+            ("d4", "not json".to_string()),
+        ];
+        for (name, json) in &workspaces {
+            fs::create_dir_all(storage.join(name)).unwrap();
+            fs::write(storage.join(name).join("workspace.json"), json).unwrap();
+        }
+
+        let mut labels: Vec<String> = vscode_workspace_storage(&home).into_iter().map(|f| f.label).collect();
+        labels.sort();
+        fs::remove_dir_all(&home).unwrap();
+
+        assert_eq!(
+            labels,
+            [
+                "VS Code workspace state: /gone/app.code-workspace (folder gone)",
+                r#"VS Code workspace state: /gone/say "folder": "file:///tmp" (folder gone)"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn decodes_workspace_folder_uris() {
+        assert_eq!(percent_decode("/Users/me/My%20Projects/caf%C3%A9"), "/Users/me/My Projects/café");
+        assert_eq!(percent_decode("/home/me/50%25-done"), "/home/me/50%-done");
+        assert_eq!(percent_decode("/home/me/100%"), "/home/me/100%");
+        assert_eq!(percent_decode("/home/me/%zz"), "/home/me/%zz");
+    }
+}
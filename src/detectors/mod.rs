@@ -0,0 +1,241 @@
+pub mod android;
+pub mod app_caches;
+pub mod conda;
+pub mod homebrew;
+pub mod ide;
+pub mod nix;
+pub mod snap_flatpak;
+pub mod swift_deps;
+pub mod system_caches;
+pub mod terraform;
+pub mod toolchains;
+pub mod vm;
+pub mod xcode;
+
+use crate::trash::{self, Deletion};
+use std::{
+    ffi::OsStr,
+    fs, io,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    time::SystemTime,
+};
+use walkdir::WalkDir;
+
+#[derive(Clone)]
+pub struct Finding {
+    pub label: String,
+    pub path: PathBuf,
+    pub size: u64,
+    pub last_used: Option<SystemTime>,
+    pub removal: Removal,
+    /// Small files deleted along with `path` that would be left dangling without it.
+    pub companions: Vec<PathBuf>,
+}
+
+/// How a finding is cleaned up: deleting its path, or running a tool that owns it.
+#[derive(Clone)]
+pub enum Removal {
+    DeletePath,
+    Command { args: Vec<String>, needs_root: bool },
+}
+
+impl Finding {
+    pub fn from_path(label: impl Into<String>, path: &Path) -> Self {
+        let (size, last_used) = measure(path);
+        Self {
+            label: label.into(),
+            path: path.to_path_buf(),
+            size,
+            last_used,
+            removal: Removal::DeletePath,
+            companions: Vec::new(),
+        }
+    }
+
+    pub fn with_companion(mut self, path: PathBuf) -> Self {
+        self.companions.push(path);
+        self
+    }
+
+    pub fn with_command(mut self, args: Vec<String>, needs_root: bool) -> Self {
+        self.removal = Removal::Command { args, needs_root };
+        self
+    }
+
+    pub fn needs_root(&self) -> bool {
+        matches!(self.removal, Removal::Command { needs_root: true, .. })
+    }
+
+    /// Removes the finding and returns the bytes it took. `deletion` decides whether a path
+    /// goes to the trash; commands clean up after themselves, and since a tool may leave part
+    /// of its directory behind, the path is measured before and after it runs.
+    pub fn remove(&self, deletion: Deletion) -> io::Result<u64> {
+        match &self.removal {
+            Removal::DeletePath => {
+                trash::delete(&self.path, deletion)?;
+                for companion in self.companions.iter().filter(|c| c.exists()) {
+                    trash::delete(companion, deletion)?;
+                }
+                Ok(self.size)
+            },
+            Removal::Command { args, needs_root } => {
+                let before = calculate_dir_size(&self.path);
+                let mut command = if *needs_root {
+                    privileged_command(args)
+                } else {
+                    let mut command = Command::new(&args[0]);
+                    command.args(&args[1..]);
+                    command
+                };
+                let output = command.stdin(Stdio::inherit()).output()?;
+                if output.status.success() {
+                    Ok(before.saturating_sub(calculate_dir_size(&self.path)))
+                } else {
+                    let stderr = String::from_utf8_lossy(&output.stderr);
+                    Err(io::Error::other(format!("{} exited with {}: {}", args[0], output.status, stderr.trim())))
+                }
+            },
+        }
+    }
+}
+
+/// What an external cleanup tool reported when it succeeded.
+pub struct ToolOutput {
+    pub freed: u64,
+    pub stderr: String,
+}
+
+#[derive(Clone)]
+pub struct Category {
+    pub name: &'static str,
+    pub findings: Vec<Finding>,
+    pub note: Option<String>,
+}
+
+impl Category {
+    pub fn new(name: &'static str, mut findings: Vec<Finding>) -> Self {
+        findings.sort_by_key(|f| std::cmp::Reverse(f.size));
+        Self { name, findings, note: None }
+    }
+
+    pub fn total_size(&self) -> u64 {
+        self.findings.iter().map(|f| f.size).sum()
+    }
+}
+
+/// Total size of all files below `path` and the newest modification time seen.
+pub fn measure(path: &Path) -> (u64, Option<SystemTime>) {
+    let mut total = 0;
+    let mut newest: Option<SystemTime> = None;
+
+    for entry in WalkDir::new(path)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|e| e.ok())
+    {
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.is_file() {
+            total += metadata.len();
+        }
+        if let Ok(modified) = metadata.modified() {
+            newest = Some(newest.map_or(modified, |n| n.max(modified)));
+        }
+    }
+
+    (total, newest)
+}
+
+pub fn calculate_dir_size(path: &Path) -> u64 {
+    measure(path).0
+}
+
+/// Immediate subdirectories of `path`, or nothing if it does not exist.
+pub fn subdirs(path: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(path) else {
+        return Vec::new();
+    };
+
+    let mut dirs: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().map(|t| t.is_dir()).unwrap_or(false))
+        .map(|e| e.path())
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+/// Dependency folders listed by [`project_dirs`] but not walked into. Hidden ones such as
+/// `.build` and `.terraform` are skipped along with every other hidden folder.
+const DEPENDENCY_DIRS: [&str; 2] = ["node_modules", "Pods"];
+
+/// Directories under `base` that may hold projects, skipping hidden and system folders. The
+/// walk is expensive, so it runs once per scan and its result is shared between detectors.
+pub fn project_dirs(base: &Path) -> Vec<PathBuf> {
+    let mut walk = WalkDir::new(base)
+        .max_depth(6)
+        .follow_links(false)
+        .into_iter()
+        .filter_entry(|e| {
+            let name = e.file_name().to_string_lossy();
+            !name.starts_with('.') &&
+            name != "Library" &&
+            name != "System" &&
+            name != "Applications"
+        });
+
+    let mut dirs = Vec::new();
+    while let Some(entry) = walk.next() {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        if !entry.file_type().is_dir() {
+            continue;
+        }
+        if DEPENDENCY_DIRS.iter().any(|name| entry.file_name() == *name) {
+            walk.skip_current_dir();
+        }
+        dirs.push(entry.into_path());
+    }
+    dirs
+}
+
+/// `args` as a command, prefixed with `sudo` unless macmon already runs as root.
+pub fn privileged_command<S: AsRef<OsStr>>(args: &[S]) -> Command {
+    let mut command = if is_root() {
+        Command::new(&args[0])
+    } else {
+        let mut sudo = Command::new("sudo");
+        sudo.arg(&args[0]);
+        sudo
+    };
+    command.args(&args[1..]);
+    command
+}
+
+fn is_root() -> bool {
+    // SAFETY: geteuid has no preconditions and cannot fail.
+    unsafe { libc::geteuid() == 0 }
+}
+
+pub fn is_installed(program: &str) -> bool {
+    std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+        .unwrap_or(false)
+}
+
+/// Numeric components of a version string, for ordering: `2024.2.1-arm64` -> `[2024, 2, 1, 64]`.
+pub fn version_key(version: &str) -> Vec<u64> {
+    version
+        .split(|c: char| !c.is_ascii_digit())
+        .filter_map(|part| part.parse().ok())
+        .collect()
+}
+
+pub fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string())
+}
@@ -0,0 +1,167 @@
+use super::{calculate_dir_size, is_installed, ToolOutput};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+    time::SystemTime,
+};
+
+#[derive(Clone)]
+pub struct Generation {
+    pub profile: String,
+    pub number: u32,
+    pub created: Option<SystemTime>,
+}
+
+#[derive(Clone)]
+pub struct NixReport {
+    pub dead_paths: usize,
+    pub dead_bytes: u64,
+    pub generations: Vec<Generation>,
+}
+
+impl NixReport {
+    /// Generations older than `days`, never counting the newest one of each profile.
+    pub fn generations_older_than(&self, days: u64) -> Vec<&Generation> {
+        self.generations
+            .iter()
+            .filter(|g| {
+                let newest = self
+                    .generations
+                    .iter()
+                    .filter(|other| other.profile == g.profile)
+                    .map(|other| other.number)
+                    .max();
+                let age = g.created.and_then(|t| t.elapsed().ok()).map(|e| e.as_secs() / 86_400);
+                Some(g.number) != newest && age.is_some_and(|age| age > days)
+            })
+            .collect()
+    }
+}
+
+pub fn scan(home: &Path) -> Option<NixReport> {
+    if !is_installed("nix-store") {
+        return None;
+    }
+
+    let dead = Command::new("nix-store")
+        .args(["--gc", "--print-dead"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
+        .unwrap_or_default();
+    let dead: Vec<&str> = dead.lines().filter(|line| line.starts_with('/')).collect();
+    let dead_bytes = dead.iter().map(|path| calculate_dir_size(Path::new(path))).sum();
+
+    Some(NixReport {
+        dead_paths: dead.len(),
+        dead_bytes,
+        generations: generations(&profile_dirs(home)),
+    })
+}
+
+fn profile_dirs(home: &Path) -> Vec<PathBuf> {
+    let mut dirs = vec![
+        home.join(".local/state/nix/profiles"),
+        PathBuf::from("/nix/var/nix/profiles"),
+    ];
+    if let Ok(user) = env::var("USER") {
+        dirs.push(PathBuf::from(format!("/nix/var/nix/profiles/per-user/{user}")));
+    }
+    dirs
+}
+
+/// Profile generations, read from the `<profile>-<number>-link` symlinks in `dirs`.
+pub fn generations(dirs: &[PathBuf]) -> Vec<Generation> {
+    let mut generations = Vec::new();
+
+    for dir in dirs {
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        for entry in entries.filter_map(|e| e.ok()) {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let Some(stem) = name.strip_suffix("-link") else {
+                continue;
+            };
+            let Some((profile, number)) = stem.rsplit_once('-') else {
+                continue;
+            };
+            let Ok(number) = number.parse() else {
+                continue;
+            };
+            let created = fs::symlink_metadata(entry.path()).and_then(|m| m.modified()).ok();
+            generations.push(Generation {
+                profile: format!("{}/{profile}", dir.display()),
+                number,
+                created,
+            });
+        }
+    }
+
+    generations.sort_by(|a, b| a.profile.cmp(&b.profile).then(a.number.cmp(&b.number)));
+    generations
+}
+
+/// Runs `nix-collect-garbage --delete-older-than <days>d` and returns the bytes it freed.
+pub fn collect_garbage(days: u64) -> Result<ToolOutput, String> {
+    let output = Command::new("nix-collect-garbage")
+        .args(["--delete-older-than", &format!("{days}d")])
+        .output()
+        .map_err(|err| format!("could not run nix-collect-garbage: {err}"))?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Ok(ToolOutput {
+        freed: stdout.lines().chain(stderr.lines()).filter_map(parse_freed).sum(),
+        stderr: stderr.to_string(),
+    })
+}
+
+/// Parses "1234 store paths deleted, 567.89 MiB freed".
+pub fn parse_freed(line: &str) -> Option<u64> {
+    let before = line.strip_suffix(" freed")?;
+    let mut parts = before.rsplit(' ');
+    let unit = parts.next()?;
+    let number: f64 = parts.next()?.parse().ok()?;
+    let multiplier = match unit {
+        "B" => 1.0,
+        "KiB" => 1024.0,
+        "MiB" => 1024.0 * 1024.0,
+        "GiB" => 1024.0 * 1024.0 * 1024.0,
+        "TiB" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    Some((number * multiplier) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_what_garbage_collection_freed() {
+        let stderr = "\
+removing old generations of profile /nix/var/nix/profiles/per-user/me/profile
+removing profile version 41
+finding garbage collector roots...
+deleting garbage...
+deleting '/nix/store/0a8k2r4l6v7xvfm1c4b9y2q3s5d6f7g8-hello-2.12.1'
+deleting '/nix/store/trash'
+deleting unused links...
+note: currently hard linking saves 1024.52 MiB
+3456 store paths deleted, 1234.56 MiB freed
+";
+        let freed: Vec<u64> = stderr.lines().filter_map(parse_freed).collect();
+        assert_eq!(freed, [1_294_529_986]);
+
+        assert_eq!(parse_freed("12 store paths deleted, 1.21 GiB freed"), Some(1_299_227_607));
+        assert_eq!(parse_freed("0 store paths deleted, 0.00 MiB freed"), Some(0));
+        assert_eq!(parse_freed("2 store paths deleted, 3 PiB freed"), None);
+    }
+}
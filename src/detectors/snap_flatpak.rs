@@ -0,0 +1,194 @@
+use super::{is_installed, Finding};
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+pub fn scan(root: &Path, home: &Path) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    if is_installed("snap")
+        && let Some(list) = run(&["snap", "list", "--all"])
+    {
+        findings.extend(disabled_snaps(&list, &root.join("var/lib/snapd/snaps")));
+    }
+
+    if is_installed("flatpak")
+        && let Some(runtimes) = run(&["flatpak", "list", "--runtime", "--columns=ref,installation"])
+    {
+        let installations = [
+            ("system", root.join("var/lib/flatpak")),
+            ("user", home.join(".local/share/flatpak")),
+        ];
+        let unused: Vec<(&str, String)> = installations
+            .iter()
+            .filter_map(|(name, _)| Some((*name, unused_preview(name)?)))
+            .collect();
+        findings.extend(unused_flatpak_runtimes(&runtimes, &unused, &installations));
+    }
+
+    findings
+}
+
+/// Disabled revisions from `snap list --all`, sized by their `.snap` file in `snaps_dir`.
+pub fn disabled_snaps(list: &str, snaps_dir: &Path) -> Vec<Finding> {
+    list.lines()
+        .skip(1)
+        .filter_map(|line| {
+            let columns: Vec<&str> = line.split_whitespace().collect();
+            let notes = columns.last()?;
+            if columns.len() < 4 || !notes.split(',').any(|n| n == "disabled") {
+                return None;
+            }
+            let (name, revision) = (columns[0], columns[2]);
+            let path = snaps_dir.join(format!("{name}_{revision}.snap"));
+            let finding = Finding::from_path(format!("Snap {name} revision {revision} (disabled)"), &path)
+                .with_command(
+                    vec![
+                        "snap".to_string(),
+                        "remove".to_string(),
+                        name.to_string(),
+                        format!("--revision={revision}"),
+                    ],
+                    true,
+                );
+            Some(finding)
+        })
+        .collect()
+}
+
+/// Runtimes from `flatpak list --runtime` that flatpak itself would remove as unused, going
+/// by each installation's `flatpak uninstall --unused` preview in `unused`. Flatpak knows which
+/// extensions, such as GL drivers or codecs, a used runtime or app still pulls in.
+pub fn unused_flatpak_runtimes(
+    runtimes: &str,
+    unused: &[(&str, String)],
+    installations: &[(&str, PathBuf)],
+) -> Vec<Finding> {
+    runtimes
+        .lines()
+        .filter_map(|line| {
+            let mut columns = line.split('\t');
+            let runtime_ref = columns.next()?.trim();
+            let installation = columns.next().unwrap_or("system").trim();
+            let mut parts = runtime_ref.split('/');
+            let (id, branch) = (parts.next()?, parts.nth(1)?);
+
+            let is_unused = unused
+                .iter()
+                .filter(|(name, _)| *name == installation)
+                .flat_map(|(_, preview)| unused_refs(preview))
+                .any(|(unused_id, unused_branch)| unused_id == id && unused_branch == branch);
+            if !is_unused {
+                return None;
+            }
+
+            let base = installations
+                .iter()
+                .find(|(name, _)| *name == installation)
+                .map(|(_, path)| path.clone())?;
+            let path = base.join("runtime").join(runtime_ref);
+            let finding = Finding::from_path(format!("Flatpak runtime {runtime_ref} (unused)"), &path)
+                .with_command(
+                    vec![
+                        "flatpak".to_string(),
+                        "uninstall".to_string(),
+                        "--noninteractive".to_string(),
+                        format!("--{installation}"),
+                        runtime_ref.to_string(),
+                    ],
+                    installation == "system",
+                );
+            Some(finding)
+        })
+        .collect()
+}
+
+/// `(id, branch)` of each ref in a `flatpak uninstall --unused` preview, whose rows look like
+/// ` 1.     org.freedesktop.Platform.GL.default  23.08  r`.
+fn unused_refs(preview: &str) -> impl Iterator<Item = (&str, &str)> {
+    preview.lines().filter_map(|line| {
+        let mut columns = line.split_whitespace();
+        let number = columns.next()?.strip_suffix('.')?;
+        number.parse::<u32>().ok()?;
+        let mut columns = columns.skip_while(|column| column.starts_with('[') || column.ends_with(']'));
+        Some((columns.next()?, columns.next()?))
+    })
+}
+
+/// The list `flatpak uninstall --unused` prints for `installation`, answering no to its prompt
+/// so nothing is removed.
+fn unused_preview(installation: &str) -> Option<String> {
+    let mut child = Command::new("flatpak")
+        .args(["uninstall", "--unused", &format!("--{installation}")])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    child.stdin.take()?.write_all(b"n\n").ok()?;
+    let output = child.wait_with_output().ok()?;
+    Some(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn run(args: &[&str]) -> Option<String> {
+    let output = Command::new(args[0]).args(&args[1..]).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_disabled_snap_revisions() {
+        let list = "\
+Name     Version          Rev    Tracking         Publisher   Notes
+core20   20230801         2015   latest/stable    canonical✓  base,disabled
+core20   20240111         2182   latest/stable    canonical✓  base
+firefox  122.0-2          3779   latest/stable/…  mozilla✓    disabled
+firefox  123.0-1          3836   latest/stable/…  mozilla✓    -
+snapd    2.61.1           20671  latest/stable    canonical✓  snapd
+";
+        let findings = disabled_snaps(list, Path::new("/var/lib/snapd/snaps"));
+
+        let labels: Vec<&str> = findings.iter().map(|f| f.label.as_str()).collect();
+        assert_eq!(labels, ["Snap core20 revision 2015 (disabled)", "Snap firefox revision 3779 (disabled)"]);
+        assert_eq!(findings[1].path, Path::new("/var/lib/snapd/snaps/firefox_3779.snap"));
+        assert!(findings.iter().all(Finding::needs_root));
+    }
+
+    #[test]
+    fn takes_unused_flatpak_runtimes_from_the_uninstall_preview() {
+        let runtimes = "\
+org.freedesktop.Platform/x86_64/23.08\tsystem
+org.freedesktop.Platform.GL.default/x86_64/23.08\tsystem
+org.freedesktop.Platform.openh264/x86_64/2.2.0\tsystem
+org.gnome.Platform/x86_64/44\tsystem
+org.gnome.Platform/x86_64/45\tsystem
+org.kde.Platform/x86_64/5.15-23.08\tuser
+";
+        let preview = "\
+        ID                           Branch         Op
+ 1.     org.gnome.Platform           44             r
+ 2.     org.gnome.Platform.Locale    44             r
+
+Proceed with these changes to the system installation? [Y/n]: n
+";
+        let installations = [
+            ("system", PathBuf::from("/var/lib/flatpak")),
+            ("user", PathBuf::from("/home/me/.local/share/flatpak")),
+        ];
+
+        let findings = unused_flatpak_runtimes(runtimes, &[("system", preview.to_string())], &installations);
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].label, "Flatpak runtime org.gnome.Platform/x86_64/44 (unused)");
+        assert_eq!(findings[0].path, Path::new("/var/lib/flatpak/runtime/org.gnome.Platform/x86_64/44"));
+        assert!(findings[0].needs_root());
+    }
+}
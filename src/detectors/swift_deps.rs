@@ -0,0 +1,92 @@
+use super::{file_name, Finding};
+use std::path::{Path, PathBuf};
+
+const MIN_SIZE: u64 = 10_000_000;
+
+pub fn scan(home: &Path, projects: &[PathBuf]) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    let cocoapods_cache = home.join("Library/Caches/CocoaPods");
+    if cocoapods_cache.is_dir() {
+        findings.push(Finding::from_path("CocoaPods cache", &cocoapods_cache));
+    }
+
+    for project in projects {
+        let name = file_name(project);
+
+        if project.join("Podfile").is_file() {
+            push_if_large(&mut findings, format!("Pods: {name}"), &project.join("Pods"));
+        }
+
+        if project.join("Cartfile").is_file() {
+            let carthage = project.join("Carthage");
+            push_if_large(&mut findings, format!("Carthage build: {name}"), &carthage.join("Build"));
+            push_if_large(&mut findings, format!("Carthage checkouts: {name}"), &carthage.join("Checkouts"));
+        }
+
+        if project.join("Package.swift").is_file() {
+            push_if_large(&mut findings, format!("SwiftPM build: {name}"), &project.join(".build"));
+        }
+    }
+
+    findings
+}
+
+fn push_if_large(findings: &mut Vec<Finding>, label: String, path: &Path) {
+    if !path.is_dir() {
+        return;
+    }
+
+    let finding = Finding::from_path(label, path);
+    if finding.size > MIN_SIZE {
+        findings.push(finding);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detectors::project_dirs;
+    use std::{env, fs};
+
+    fn large_dir(path: &Path) {
+        fs::create_dir_all(path).unwrap();
+        fs::File::create(path.join("blob")).unwrap().set_len(MIN_SIZE + 1).unwrap();
+    }
+
+    #[test]
+    fn flags_dependency_dirs_only_next_to_their_manifest() {
+        let home = env::temp_dir().join(format!("macmon-swift-deps-{}", std::process::id()));
+        let code = home.join("code");
+        for (manifest, dirs) in [
+            (Some("ios-app/Podfile"), &["ios-app/Pods"][..]),
+            (Some("mac-app/Cartfile"), &["mac-app/Carthage/Build", "mac-app/Carthage/Checkouts"][..]),
+            (Some("kit/Package.swift"), &["kit/.build"][..]),
+            (None, &["no-podfile/Pods", "no-cartfile/Carthage/Build", "no-package/.build"][..]),
+        ] {
+            if let Some(manifest) = manifest {
+                fs::create_dir_all(code.join(manifest).parent().unwrap()).unwrap();
+                fs::write(code.join(manifest), "").unwrap();
+            }
+            for dir in dirs {
+                large_dir(&code.join(dir));
+            }
+        }
+        fs::create_dir_all(code.join("small/Pods")).unwrap();
+        fs::write(code.join("small/Podfile"), "").unwrap();
+
+        let mut labels: Vec<String> = scan(&home, &project_dirs(&home)).into_iter().map(|f| f.label).collect();
+        labels.sort();
+        fs::remove_dir_all(&home).unwrap();
+
+        assert_eq!(
+            labels,
+            [
+                "Carthage build: mac-app",
+                "Carthage checkouts: mac-app",
+                "Pods: ios-app",
+                "SwiftPM build: kit",
+            ]
+        );
+    }
+}
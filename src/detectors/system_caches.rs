@@ -0,0 +1,80 @@
+use super::{calculate_dir_size, is_installed, privileged_command};
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+#[derive(Clone)]
+pub struct SystemCache {
+    pub name: &'static str,
+    pub path: PathBuf,
+    pub size: u64,
+    pub command: &'static [&'static str],
+}
+
+impl SystemCache {
+    /// The cleanup command, run through [`privileged_command`].
+    pub fn privileged_command(&self) -> Command {
+        privileged_command(self.command)
+    }
+
+    pub fn refresh_size(&mut self) {
+        self.size = calculate_dir_size(&self.path);
+    }
+}
+
+const KNOWN_CACHES: [(&str, &str, &[&str]); 5] = [
+    ("apt archives", "var/cache/apt/archives", &["apt-get", "clean"]),
+    ("dnf cache", "var/cache/dnf", &["dnf", "clean", "all"]),
+    ("yum cache", "var/cache/yum", &["yum", "clean", "all"]),
+    ("pacman cache", "var/cache/pacman/pkg", &["paccache", "-r"]),
+    ("systemd journal", "var/log/journal", &["journalctl", "--vacuum-size=100M"]),
+];
+
+/// Package manager caches and the journal under `root`, limited to those whose cleanup
+/// tool is installed.
+pub fn scan(root: &Path) -> Vec<SystemCache> {
+    scan_with(root, is_installed)
+}
+
+/// [`scan`], with `installed` telling which cleanup tools are available.
+fn scan_with(root: &Path, installed: impl Fn(&str) -> bool) -> Vec<SystemCache> {
+    KNOWN_CACHES
+        .iter()
+        .filter(|(_, _, command)| installed(command[0]))
+        .map(|&(name, path, command)| {
+            let path = root.join(path);
+            let size = calculate_dir_size(&path);
+            SystemCache { name, path, size, command }
+        })
+        .filter(|cache| cache.path.is_dir() && cache.size > 0)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, fs};
+
+    #[test]
+    fn finds_caches_whose_cleanup_tool_is_installed() {
+        let root = env::temp_dir().join(format!("macmon-system-caches-{}", std::process::id()));
+        for (dir, file) in [
+            ("var/cache/apt/archives", "curl_8.5.0_amd64.deb"),
+            ("var/cache/dnf", "fedora-filenames.solvx"),
+            ("var/cache/pacman/pkg", "linux-6.7.arch1-1-x86_64.pkg.tar.zst"),
+            ("var/log/journal", "system.journal"),
+        ] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+            fs::write(root.join(dir).join(file), [0; 32]).unwrap();
+        }
+        fs::create_dir_all(root.join("var/cache/yum")).unwrap();
+
+        let caches = scan_with(&root, |program| program != "paccache");
+        fs::remove_dir_all(&root).unwrap();
+
+        let found: Vec<(&str, u64)> = caches.iter().map(|cache| (cache.name, cache.size)).collect();
+        assert_eq!(found, [("apt archives", 32), ("dnf cache", 32), ("systemd journal", 32)]);
+        assert_eq!(caches[2].command, ["journalctl", "--vacuum-size=100M"]);
+    }
+}
@@ -0,0 +1,104 @@
+use super::{file_name, subdirs, Finding};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+use walkdir::WalkDir;
+
+pub struct TerraformReport {
+    pub findings: Vec<Finding>,
+    /// Bytes that would be saved if every stack shared one provider plugin cache.
+    pub shared_cache_savings: u64,
+}
+
+pub fn scan(home: &Path, projects: &[PathBuf]) -> TerraformReport {
+    let mut findings = Vec::new();
+    let mut providers: HashMap<String, u64> = HashMap::new();
+    let mut providers_total = 0;
+
+    for stack in projects {
+        let name = file_name(stack);
+
+        let terraform = stack.join(".terraform");
+        if terraform.is_dir() {
+            findings.push(Finding::from_path(format!("Terraform: {name}"), &terraform));
+            providers_total += collect_providers(&terraform.join("providers"), &mut providers);
+        }
+
+        let terragrunt = stack.join(".terragrunt-cache");
+        if terragrunt.is_dir() {
+            findings.push(Finding::from_path(format!("Terragrunt cache: {name}"), &terragrunt));
+        }
+    }
+
+    let plugin_cache = home.join(".terraform.d/plugin-cache");
+    if plugin_cache.is_dir() {
+        findings.push(Finding::from_path("Terraform plugin cache", &plugin_cache));
+    }
+
+    for plugin in subdirs(&home.join(".pulumi/plugins")) {
+        findings.push(Finding::from_path(format!("Pulumi plugin: {}", file_name(&plugin)), &plugin));
+    }
+
+    let unique: u64 = providers.values().sum();
+    TerraformReport {
+        findings,
+        shared_cache_savings: providers_total - unique,
+    }
+}
+
+/// Adds every provider binary under `providers` to `seen`, keyed by its path relative to
+/// the providers directory, and returns the bytes found. Symlinks into an existing plugin
+/// cache are not counted.
+fn collect_providers(providers: &Path, seen: &mut HashMap<String, u64>) -> u64 {
+    let mut total = 0;
+
+    for entry in WalkDir::new(providers)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+    {
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        let Ok(relative) = entry.path().strip_prefix(providers) else {
+            continue;
+        };
+        total += metadata.len();
+        seen.insert(relative.to_string_lossy().to_string(), metadata.len());
+    }
+
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, fs};
+
+    const AWS: &str = "registry.terraform.io/hashicorp/aws/5.31.0/darwin_arm64/terraform-provider-aws_v5.31.0_x5";
+    const RANDOM: &str = "registry.terraform.io/hashicorp/random/3.6.0/darwin_arm64/terraform-provider-random_v3.6.0_x5";
+
+    #[test]
+    fn counts_what_a_shared_plugin_cache_would_save() {
+        let home = env::temp_dir().join(format!("macmon-terraform-{}", std::process::id()));
+        let stacks = [home.join("infra/network"), home.join("infra/app"), home.join("infra/empty")];
+        for (stack, providers) in stacks.iter().zip([&[AWS, RANDOM][..], &[AWS][..], &[][..]]) {
+            fs::create_dir_all(stack).unwrap();
+            for provider in providers {
+                let binary = stack.join(".terraform/providers").join(provider);
+                fs::create_dir_all(binary.parent().unwrap()).unwrap();
+                let size = if *provider == AWS { 4000 } else { 300 };
+                fs::write(binary, vec![0; size]).unwrap();
+            }
+        }
+
+        let report = scan(&home, &stacks);
+
+        let labels: Vec<&str> = report.findings.iter().map(|f| f.label.as_str()).collect();
+        assert_eq!(labels, ["Terraform: network", "Terraform: app"]);
+        assert_eq!(report.shared_cache_savings, 4000);
+        fs::remove_dir_all(&home).unwrap();
+    }
+}
@@ -0,0 +1,334 @@
+use super::{file_name, is_installed, subdirs, version_key, Finding};
+use serde_json::Value;
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+struct Installed {
+    manager: &'static str,
+    language: String,
+    version: String,
+    path: PathBuf,
+}
+
+/// Version files a project may pin a toolchain with.
+const VERSION_FILES: [&str; 8] = [
+    "rust-toolchain.toml",
+    "rust-toolchain",
+    ".nvmrc",
+    ".node-version",
+    ".python-version",
+    ".ruby-version",
+    ".tool-versions",
+    ".sdkmanrc",
+];
+
+/// Installed toolchain versions that no version file in `projects`, and no manager default,
+/// refers to.
+pub fn scan(home: &Path, projects: &[PathBuf]) -> Vec<Finding> {
+    let installed = installed_versions(home);
+    let mut references = global_references(home);
+    for dir in projects {
+        references.extend(version_file_references(dir));
+    }
+
+    let mut by_language: HashMap<&str, Vec<&str>> = HashMap::new();
+    for (language, version) in &references {
+        by_language.entry(language.as_str()).or_default().push(version.as_str());
+    }
+
+    installed
+        .iter()
+        .filter(|toolchain| {
+            let refs = by_language.get(toolchain.language.as_str()).map_or(&[][..], |r| r.as_slice());
+            !refs.iter().any(|reference| is_referenced(toolchain, reference, &installed))
+        })
+        .map(|toolchain| {
+            let label = format!(
+                "{} {} {} (unreferenced)",
+                toolchain.manager, toolchain.language, toolchain.version
+            );
+            let finding = Finding::from_path(label, &toolchain.path);
+            if toolchain.manager == "rustup" && is_installed("rustup") {
+                finding.with_command(
+                    vec![
+                        "rustup".to_string(),
+                        "toolchain".to_string(),
+                        "uninstall".to_string(),
+                        toolchain.version.clone(),
+                    ],
+                    false,
+                )
+            } else {
+                finding
+            }
+        })
+        .collect()
+}
+
+fn installed_versions(home: &Path) -> Vec<Installed> {
+    let mut installed = Vec::new();
+    let mut add = |manager: &'static str, language: &str, dir: &Path| {
+        for version in subdirs(dir) {
+            let name = file_name(&version);
+            if name == "current" {
+                continue;
+            }
+            installed.push(Installed {
+                manager,
+                language: language.to_string(),
+                version: name.trim_start_matches('v').to_string(),
+                path: version,
+            });
+        }
+    };
+
+    add("rustup", "rust", &home.join(".rustup/toolchains"));
+    add("nvm", "node", &home.join(".nvm/versions/node"));
+    add("fnm", "node", &home.join(".local/share/fnm/node-versions"));
+    add("fnm", "node", &home.join("Library/Application Support/fnm/node-versions"));
+    add("volta", "node", &home.join(".volta/tools/image/node"));
+    add("pyenv", "python", &home.join(".pyenv/versions"));
+    add("rbenv", "ruby", &home.join(".rbenv/versions"));
+
+    for candidate in subdirs(&home.join(".sdkman/candidates")) {
+        add("sdkman", &file_name(&candidate), &candidate);
+    }
+    for plugin in subdirs(&home.join(".asdf/installs")) {
+        add("asdf", normalize_language(&file_name(&plugin)), &plugin);
+    }
+
+    installed
+}
+
+/// Versions the managers themselves default to, which must never be offered for removal.
+fn global_references(home: &Path) -> Vec<(String, String)> {
+    let mut references = Vec::new();
+
+    if let Ok(settings) = fs::read_to_string(home.join(".rustup/settings.toml"))
+        && let Some(default) = toml_string(&settings, "default_toolchain")
+    {
+        references.push(("rust".to_string(), default));
+    }
+    if let Ok(alias) = fs::read_to_string(home.join(".nvm/alias/default")) {
+        references.push(("node".to_string(), alias.trim().to_string()));
+    }
+    for alias in [
+        home.join(".local/share/fnm/aliases/default"),
+        home.join("Library/Application Support/fnm/aliases/default"),
+    ] {
+        if let Ok(target) = fs::read_link(alias) {
+            references.push(("node".to_string(), file_name(&target)));
+        }
+    }
+    if let Ok(platform) = fs::read_to_string(home.join(".volta/tools/user/platform.json"))
+        && let Ok(platform) = serde_json::from_str::<Value>(&platform)
+        && let Some(runtime) = platform["node"]["runtime"].as_str()
+    {
+        references.push(("node".to_string(), runtime.to_string()));
+    }
+    for (language, file) in [("python", ".pyenv/version"), ("ruby", ".rbenv/version")] {
+        if let Ok(contents) = fs::read_to_string(home.join(file)) {
+            for line in contents.lines().map(str::trim).filter(|l| !l.is_empty()) {
+                references.push((language.to_string(), line.to_string()));
+            }
+        }
+    }
+    for candidate in subdirs(&home.join(".sdkman/candidates")) {
+        if let Ok(target) = fs::read_link(candidate.join("current")) {
+            references.push((file_name(&candidate), file_name(&target)));
+        }
+    }
+
+    references
+}
+
+/// `(language, version)` pairs from the version files in `dir`. The directory is listed once
+/// and only the version files it holds are read.
+fn version_file_references(dir: &Path) -> Vec<(String, String)> {
+    let mut references = Vec::new();
+    let Ok(entries) = fs::read_dir(dir) else {
+        return references;
+    };
+    let present: HashSet<String> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|name| VERSION_FILES.contains(&name.as_str()))
+        .collect();
+    if present.is_empty() {
+        return references;
+    }
+    let read = |name: &str| {
+        present
+            .contains(name)
+            .then(|| fs::read_to_string(dir.join(name)).ok())
+            .flatten()
+    };
+    let first_line = |contents: String| {
+        contents.lines().map(str::trim).find(|l| !l.is_empty()).map(String::from)
+    };
+
+    for name in ["rust-toolchain.toml", "rust-toolchain"] {
+        if let Some(contents) = read(name) {
+            let channel = toml_string(&contents, "channel").or_else(|| first_line(contents));
+            if let Some(channel) = channel {
+                references.push(("rust".to_string(), channel));
+            }
+        }
+    }
+    for name in [".nvmrc", ".node-version"] {
+        if let Some(version) = read(name).and_then(first_line) {
+            references.push(("node".to_string(), version));
+        }
+    }
+    if let Some(contents) = read(".python-version") {
+        for line in contents.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            references.push(("python".to_string(), line.to_string()));
+        }
+    }
+    if let Some(version) = read(".ruby-version").and_then(first_line) {
+        references.push(("ruby".to_string(), version.trim_start_matches("ruby-").to_string()));
+    }
+    if let Some(contents) = read(".tool-versions") {
+        for line in contents.lines() {
+            let mut parts = line.split_whitespace();
+            let Some(plugin) = parts.next().filter(|p| !p.starts_with('#')) else {
+                continue;
+            };
+            for version in parts {
+                references.push((normalize_language(plugin).to_string(), version.to_string()));
+            }
+        }
+    }
+    if let Some(contents) = read(".sdkmanrc") {
+        for line in contents.lines().filter(|l| !l.trim_start().starts_with('#')) {
+            if let Some((candidate, version)) = line.split_once('=') {
+                references.push((candidate.trim().to_string(), version.trim().to_string()));
+            }
+        }
+    }
+
+    references
+}
+
+/// Whether `reference` selects `toolchain`. Exact versions and prefixes like `18` or `3.11`
+/// match directly; aliases such as `lts/*` or `system` keep the newest installed version.
+fn is_referenced(toolchain: &Installed, reference: &str, installed: &[Installed]) -> bool {
+    let reference = reference.trim_start_matches('v');
+    let version = toolchain.version.as_str();
+
+    if version == reference
+        || version.starts_with(&format!("{reference}."))
+        || version.starts_with(&format!("{reference}-"))
+    {
+        return true;
+    }
+
+    let is_alias = toolchain.language != "rust" && !reference.starts_with(|c: char| c.is_ascii_digit());
+    is_alias
+        && installed
+            .iter()
+            .filter(|other| other.manager == toolchain.manager && other.language == toolchain.language)
+            .max_by_key(|other| version_key(&other.version))
+            .is_some_and(|newest| newest.path == toolchain.path)
+}
+
+fn normalize_language(plugin: &str) -> &str {
+    match plugin {
+        "nodejs" => "node",
+        "golang" => "go",
+        other => other,
+    }
+}
+
+fn toml_string(contents: &str, key: &str) -> Option<String> {
+    contents.lines().find_map(|line| {
+        let (name, value) = line.split_once('=')?;
+        (name.trim() == key).then(|| value.trim().trim_matches('"').to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn installed(manager: &'static str, language: &str, version: &str) -> Installed {
+        Installed {
+            manager,
+            language: language.to_string(),
+            version: version.to_string(),
+            path: PathBuf::from(format!("/toolchains/{manager}/{language}/{version}")),
+        }
+    }
+
+    #[test]
+    fn reads_every_kind_of_version_file() {
+        let dir = env::temp_dir().join(format!("macmon-toolchains-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for (name, contents) in [
+            ("rust-toolchain.toml", "[toolchain]\nchannel = \"1.75.0\"\ncomponents = [\"clippy\"]\n"),
+            (".nvmrc", "v18.19.0\n"),
+            (".python-version", "3.11.7\n3.12.1\n"),
+            (".ruby-version", "ruby-3.2.2\n"),
+            (".tool-versions", "# pinned\nnodejs 20.10.0\ngolang 1.21.5 1.20.12\n"),
+            (".sdkmanrc", "# Enable auto-env through the sdkman_auto_env config\njava=17.0.9-tem\n"),
+            ("README.md", "node 16\n"),
+        ] {
+            fs::write(dir.join(name), contents).unwrap();
+        }
+
+        let mut references = version_file_references(&dir);
+        references.sort();
+
+        let expected = [
+            ("go", "1.20.12"),
+            ("go", "1.21.5"),
+            ("java", "17.0.9-tem"),
+            ("node", "20.10.0"),
+            ("node", "v18.19.0"),
+            ("python", "3.11.7"),
+            ("python", "3.12.1"),
+            ("ruby", "3.2.2"),
+            ("rust", "1.75.0"),
+        ];
+        let references: Vec<(&str, &str)> = references.iter().map(|(l, v)| (l.as_str(), v.as_str())).collect();
+        assert_eq!(references, expected);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn reads_the_volta_default_node() {
+        let home = env::temp_dir().join(format!("macmon-volta-{}", std::process::id()));
+        let user = home.join(".volta/tools/user");
+        fs::create_dir_all(&user).unwrap();
+        fs::write(user.join("platform.json"), r#"{"node":{"runtime":"20.11.0","npm":null},"pnpm":null,"yarn":"1.22.19"}"#)
+            .unwrap();
+
+        assert_eq!(global_references(&home), [("node".to_string(), "20.11.0".to_string())]);
+        fs::remove_dir_all(&home).unwrap();
+    }
+
+    #[test]
+    fn matches_references_to_installed_versions() {
+        let all = [
+            installed("nvm", "node", "18.19.0"),
+            installed("nvm", "node", "20.10.0"),
+            installed("pyenv", "python", "3.11.7"),
+            installed("rustup", "rust", "stable-aarch64-apple-darwin"),
+            installed("rustup", "rust", "1.75.0-aarch64-apple-darwin"),
+        ];
+
+        assert!(is_referenced(&all[0], "v18.19.0", &all));
+        assert!(is_referenced(&all[0], "18", &all));
+        assert!(!is_referenced(&all[0], "18.1", &all));
+        assert!(is_referenced(&all[1], "lts/*", &all));
+        assert!(!is_referenced(&all[0], "lts/*", &all));
+        assert!(is_referenced(&all[2], "3.11", &all));
+        assert!(is_referenced(&all[3], "stable", &all));
+        assert!(is_referenced(&all[4], "1.75.0", &all));
+        assert!(!is_referenced(&all[4], "nightly", &all));
+    }
+}
@@ -0,0 +1,155 @@
+use super::{file_name, is_installed, subdirs, Finding};
+use std::{fs, path::Path};
+
+pub fn scan(root: &Path, home: &Path) -> Vec<Finding> {
+    scan_with(root, home, is_installed)
+}
+
+/// [`scan`], removing through each VM tool for which `installed` holds and deleting the
+/// files directly otherwise.
+fn scan_with(root: &Path, home: &Path, installed: impl Fn(&str) -> bool) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    for box_dir in subdirs(&home.join(".vagrant.d/boxes")) {
+        let name = file_name(&box_dir).replace("-VAGRANTSLASH-", "/");
+        for version in subdirs(&box_dir) {
+            let version_name = file_name(&version);
+            let finding = Finding::from_path(format!("Vagrant box: {name} {version_name}"), &version);
+            findings.push(if installed("vagrant") {
+                finding.with_command(
+                    command(&["vagrant", "box", "remove", &name, "--force", "--box-version", &version_name]),
+                    false,
+                )
+            } else {
+                finding
+            });
+        }
+    }
+
+    for vm in subdirs(&home.join("VirtualBox VMs")) {
+        let name = file_name(&vm);
+        let finding = Finding::from_path(format!("VirtualBox VM: {name}"), &vm);
+        findings.push(if installed("VBoxManage") {
+            finding.with_command(command(&["VBoxManage", "unregistervm", &name, "--delete"]), false)
+        } else {
+            finding
+        });
+    }
+
+    for images in [
+        home.join(".local/share/libvirt/images"),
+        root.join("var/lib/libvirt/images"),
+    ] {
+        let Ok(entries) = fs::read_dir(&images) else {
+            continue;
+        };
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            let is_disk = path
+                .extension()
+                .is_some_and(|ext| ext == "qcow2" || ext == "img");
+            if is_disk && path.is_file() {
+                findings.push(Finding::from_path(format!("QEMU disk: {}", file_name(&path)), &path));
+            }
+        }
+    }
+
+    for bundle in subdirs(&home.join("Library/Containers/com.utmapp.UTM/Data/Documents")) {
+        let name = file_name(&bundle);
+        if let Some(name) = name.strip_suffix(".utm") {
+            findings.push(Finding::from_path(format!("UTM VM: {name}"), &bundle));
+        }
+    }
+
+    for instances in [
+        root.join("var/snap/multipass/common/data/multipassd/vault/instances"),
+        root.join("var/root/Library/Application Support/multipassd/qemu/vault/instances"),
+    ] {
+        for instance in subdirs(&instances) {
+            let name = file_name(&instance);
+            let finding = Finding::from_path(format!("Multipass instance: {name}"), &instance);
+            findings.push(if installed("multipass") {
+                finding.with_command(command(&["multipass", "delete", "--purge", &name]), false)
+            } else {
+                finding
+            });
+        }
+    }
+
+    for instance in subdirs(&home.join(".lima")) {
+        let name = file_name(&instance);
+        if name.starts_with('_') {
+            continue;
+        }
+        let finding = Finding::from_path(format!("Lima instance: {name}"), &instance);
+        findings.push(if installed("limactl") {
+            finding.with_command(command(&["limactl", "delete", "--force", &name]), false)
+        } else {
+            finding
+        });
+    }
+
+    findings
+}
+
+fn command(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detectors::Removal;
+    use std::env;
+
+    fn removals(findings: &[Finding]) -> Vec<(&str, Option<&str>)> {
+        findings
+            .iter()
+            .map(|finding| {
+                let program = match &finding.removal {
+                    Removal::Command { args, .. } => Some(args[0].as_str()),
+                    Removal::DeletePath => None,
+                };
+                (finding.label.as_str(), program)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn removes_through_installed_tools_and_deletes_directly_otherwise() {
+        let root = env::temp_dir().join(format!("macmon-vm-{}", std::process::id()));
+        let home = root.join("home");
+        for dir in [
+            home.join(".vagrant.d/boxes/hashicorp-VAGRANTSLASH-bionic64/1.0.282504"),
+            home.join("VirtualBox VMs/Windows 11"),
+            home.join("Library/Containers/com.utmapp.UTM/Data/Documents/Debian.utm"),
+            home.join(".lima/default"),
+            home.join(".lima/_config"),
+            root.join("var/snap/multipass/common/data/multipassd/vault/instances/primary"),
+        ] {
+            fs::create_dir_all(dir).unwrap();
+        }
+        let images = home.join(".local/share/libvirt/images");
+        fs::create_dir_all(&images).unwrap();
+        fs::write(images.join("fedora.qcow2"), [0; 16]).unwrap();
+        fs::write(images.join("notes.txt"), "").unwrap();
+
+        let with_tools = scan_with(&root, &home, |program| program != "VBoxManage");
+        let without_tools = scan_with(&root, &home, |_| false);
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(
+            removals(&with_tools),
+            [
+                ("Vagrant box: hashicorp/bionic64 1.0.282504", Some("vagrant")),
+                ("VirtualBox VM: Windows 11", None),
+                ("QEMU disk: fedora.qcow2", None),
+                ("UTM VM: Debian", None),
+                ("Multipass instance: primary", Some("multipass")),
+                ("Lima instance: default", Some("limactl")),
+            ]
+        );
+        assert!(removals(&without_tools).iter().all(|(_, program)| program.is_none()));
+        assert_eq!(without_tools.len(), 6);
+    }
+}
@@ -0,0 +1,115 @@
+use super::{file_name, subdirs, Finding};
+use std::{fs, path::Path};
+
+const DEVICE_SUPPORT_DIRS: [&str; 3] = ["iOS DeviceSupport", "watchOS DeviceSupport", "tvOS DeviceSupport"];
+
+pub fn scan(home: &Path) -> Vec<Finding> {
+    let developer = home.join("Library/Developer");
+    let xcode = developer.join("Xcode");
+    let mut findings = Vec::new();
+
+    for dir in subdirs(&xcode.join("DerivedData")) {
+        let name = file_name(&dir);
+        let project = match name.rsplit_once('-') {
+            Some((project, _hash)) => project.to_string(),
+            None => name,
+        };
+        findings.push(Finding::from_path(format!("DerivedData: {project}"), &dir));
+    }
+
+    for day in subdirs(&xcode.join("Archives")) {
+        for archive in subdirs(&day) {
+            let name = file_name(&archive);
+            let name = name.trim_end_matches(".xcarchive");
+            findings.push(Finding::from_path(format!("Archive: {name}"), &archive));
+        }
+    }
+
+    for support in DEVICE_SUPPORT_DIRS {
+        for version in subdirs(&xcode.join(support)) {
+            findings.push(Finding::from_path(
+                format!("{support}: {}", file_name(&version)),
+                &version,
+            ));
+        }
+    }
+
+    let simulators = developer.join("CoreSimulator");
+    for device in subdirs(&simulators.join("Devices")) {
+        let name = simulator_name(&device).unwrap_or_else(|| file_name(&device));
+        findings.push(Finding::from_path(format!("Simulator: {name}"), &device));
+    }
+
+    let caches = simulators.join("Caches");
+    if caches.is_dir() {
+        findings.push(Finding::from_path("Simulator caches", &caches));
+    }
+
+    findings
+}
+
+fn simulator_name(device: &Path) -> Option<String> {
+    let plist = fs::read_to_string(device.join("device.plist")).ok()?;
+    let after_key = plist.split("<key>name</key>").nth(1)?;
+    let value = after_key.split("<string>").nth(1)?;
+    let name = value.split("</string>").next()?.trim();
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    const DEVICE_PLIST: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>UDID</key>
+	<string>5A3C1F4E-7B2D-4C8A-9E61-0D2F3B4A5C6D</string>
+	<key>deviceType</key>
+	<string>com.apple.CoreSimulator.SimDeviceType.iPhone-15-Pro</string>
+	<key>name</key>
+	<string>iPhone 15 Pro</string>
+	<key>runtime</key>
+	<string>com.apple.CoreSimulator.SimRuntime.iOS-17-2</string>
+	<key>state</key>
+	<integer>1</integer>
+</dict>
+</plist>
+"#;
+
+    #[test]
+    fn finds_xcode_artifacts_under_home() {
+        let home = env::temp_dir().join(format!("macmon-xcode-{}", std::process::id()));
+        let developer = home.join("Library/Developer");
+        let xcode = developer.join("Xcode");
+        for dir in [
+            "DerivedData/MyApp-bqzfwxkdhjsuaxgehmxkjzlmpnkd",
+            "Archives/2024-01-15/MyApp 15-01-2024, 10.32.xcarchive",
+            "iOS DeviceSupport/17.2 (21C62)",
+        ] {
+            fs::create_dir_all(xcode.join(dir)).unwrap();
+        }
+        let devices = developer.join("CoreSimulator/Devices");
+        fs::create_dir_all(devices.join("5A3C1F4E-7B2D-4C8A-9E61-0D2F3B4A5C6D")).unwrap();
+        fs::write(devices.join("5A3C1F4E-7B2D-4C8A-9E61-0D2F3B4A5C6D/device.plist"), DEVICE_PLIST).unwrap();
+        fs::create_dir_all(devices.join("9F8E7D6C-0000-1111-2222-333344445555")).unwrap();
+        fs::create_dir_all(developer.join("CoreSimulator/Caches")).unwrap();
+
+        let labels: Vec<String> = scan(&home).into_iter().map(|f| f.label).collect();
+
+        assert_eq!(
+            labels,
+            [
+                "DerivedData: MyApp",
+                "Archive: MyApp 15-01-2024, 10.32",
+                "iOS DeviceSupport: 17.2 (21C62)",
+                "Simulator: iPhone 15 Pro",
+                "Simulator: 9F8E7D6C-0000-1111-2222-333344445555",
+                "Simulator caches",
+            ]
+        );
+        fs::remove_dir_all(&home).unwrap();
+    }
+}
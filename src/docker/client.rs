@@ -0,0 +1,200 @@
+use serde::de::DeserializeOwned;
+use std::{
+    env, fs,
+    io::{self, Read, Write},
+    os::unix::net::UnixStream,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+const DEFAULT_SOCKETS: [&str; 2] = ["/var/run/docker.sock", ".docker/run/docker.sock"];
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A minimal Docker Engine API client speaking HTTP/1.1 over a Unix socket.
+#[derive(Clone)]
+pub struct DockerClient {
+    socket: PathBuf,
+}
+
+impl DockerClient {
+    pub fn new(socket: impl Into<PathBuf>) -> Self {
+        Self { socket: socket.into() }
+    }
+
+    /// Finds the daemon socket the way the `docker` CLI does: `DOCKER_HOST`, then the
+    /// `DOCKER_CONTEXT` or current context from `~/.docker/config.json`, then the default
+    /// socket locations. A host that is not a Unix socket, such as `tcp://`, is returned as the
+    /// error so it can be reported instead of silently ignored.
+    pub fn from_env(home: &Path) -> Result<Option<Self>, String> {
+        if let Ok(host) = env::var("DOCKER_HOST") {
+            return client_for(&host).map(Some);
+        }
+
+        let context = env::var("DOCKER_CONTEXT").ok().or_else(|| current_context(home));
+        if let Some(context) = context.filter(|c| c != "default") {
+            return context_host(home, &context).map(|host| client_for(&host)).transpose();
+        }
+
+        Ok(DEFAULT_SOCKETS
+            .iter()
+            .map(|socket| home.join(socket))
+            .find(|socket| socket.exists())
+            .map(Self::new))
+    }
+
+    pub fn socket(&self) -> &Path {
+        &self.socket
+    }
+
+    pub fn get<T: DeserializeOwned>(&self, path: &str) -> io::Result<T> {
+        self.request_json("GET", path)
+    }
+
+    pub fn post<T: DeserializeOwned>(&self, path: &str) -> io::Result<T> {
+        self.request_json("POST", path)
+    }
+
+    pub fn delete<T: DeserializeOwned>(&self, path: &str) -> io::Result<T> {
+        self.request_json("DELETE", path)
+    }
+
+    fn request_json<T: DeserializeOwned>(&self, method: &str, path: &str) -> io::Result<T> {
+        let mut body = self.request(method, path)?;
+        // 204 No Content, as returned when deleting a container, volume or network.
+        if body.is_empty() {
+            body = b"null".to_vec();
+        }
+        serde_json::from_slice(&body).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    /// Sends a request and returns the response body, or an error carrying the daemon's
+    /// message for non-2xx responses.
+    fn request(&self, method: &str, path: &str) -> io::Result<Vec<u8>> {
+        let mut stream = UnixStream::connect(&self.socket)?;
+        stream.set_read_timeout(Some(TIMEOUT))?;
+        stream.set_write_timeout(Some(TIMEOUT))?;
+
+        write!(
+            stream,
+            "{method} {path} HTTP/1.1\r\nHost: docker\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+        )?;
+
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response)?;
+
+        let (status, body) = parse_response(&response)?;
+        if (200..300).contains(&status) {
+            Ok(body)
+        } else {
+            let message = serde_json::from_slice::<serde_json::Value>(&body)
+                .ok()
+                .and_then(|v| v["message"].as_str().map(String::from))
+                .unwrap_or_else(|| String::from_utf8_lossy(&body).trim().to_string());
+            Err(io::Error::other(format!("docker API {status}: {message}")))
+        }
+    }
+}
+
+fn parse_response(response: &[u8]) -> io::Result<(u16, Vec<u8>)> {
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+
+    let split = response
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .ok_or_else(|| invalid("incomplete HTTP response"))?;
+    let head = String::from_utf8_lossy(&response[..split]);
+    let body = &response[split + 4..];
+
+    let status = head
+        .lines()
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(|| invalid("malformed HTTP status line"))?;
+
+    let chunked = head.lines().any(|line| {
+        let line = line.to_ascii_lowercase();
+        line.starts_with("transfer-encoding:") && line.contains("chunked")
+    });
+
+    let body = if chunked {
+        decode_chunked(body).ok_or_else(|| invalid("malformed chunked body"))?
+    } else {
+        body.to_vec()
+    };
+    Ok((status, body))
+}
+
+fn decode_chunked(mut body: &[u8]) -> Option<Vec<u8>> {
+    let mut decoded = Vec::new();
+
+    loop {
+        let line_end = body.windows(2).position(|w| w == b"\r\n")?;
+        let size_line = std::str::from_utf8(&body[..line_end]).ok()?;
+        let size = usize::from_str_radix(size_line.split(';').next()?.trim(), 16).ok()?;
+        body = &body[line_end + 2..];
+        if size == 0 {
+            return Some(decoded);
+        }
+        decoded.extend_from_slice(body.get(..size)?);
+        body = body.get(size + 2..)?;
+    }
+}
+
+pub(super) fn unix_socket(host: &str) -> Option<PathBuf> {
+    host.strip_prefix("unix://").map(PathBuf::from)
+}
+
+fn client_for(host: &str) -> Result<DockerClient, String> {
+    unix_socket(host).map(DockerClient::new).ok_or_else(|| host.to_string())
+}
+
+fn current_context(home: &Path) -> Option<String> {
+    let config = fs::read_to_string(home.join(".docker/config.json")).ok()?;
+    let config: serde_json::Value = serde_json::from_str(&config).ok()?;
+    config["currentContext"].as_str().map(String::from)
+}
+
+/// The Docker endpoint of a named context, read from `~/.docker/contexts/meta/*/meta.json`.
+fn context_host(home: &Path, context: &str) -> Option<String> {
+    fs::read_dir(home.join(".docker/contexts/meta"))
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| fs::read_to_string(entry.path().join("meta.json")).ok())
+        .filter_map(|meta| serde_json::from_str::<serde_json::Value>(&meta).ok())
+        .find(|meta| meta["Name"] == context)
+        .and_then(|meta| meta["Endpoints"]["docker"]["Host"].as_str().map(String::from))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_current_context_endpoint() {
+        let home = env::temp_dir().join(format!("macmon-context-{}", std::process::id()));
+        let meta = home.join(".docker/contexts/meta/0123abcd");
+        fs::create_dir_all(&meta).unwrap();
+        fs::write(home.join(".docker/config.json"), r#"{"currentContext": "colima"}"#).unwrap();
+        fs::write(
+            meta.join("meta.json"),
+            r#"{"Name": "colima", "Endpoints": {"docker": {"Host": "unix:///tmp/colima.sock"}}}"#,
+        )
+        .unwrap();
+
+        let context = current_context(&home).unwrap();
+        let host = context_host(&home, &context).unwrap();
+        fs::remove_dir_all(&home).unwrap();
+
+        assert_eq!(context, "colima");
+        assert_eq!(unix_socket(&host), Some(PathBuf::from("/tmp/colima.sock")));
+        assert_eq!(unix_socket("tcp://10.0.0.2:2375"), None);
+        assert_eq!(client_for("tcp://10.0.0.2:2375").err().as_deref(), Some("tcp://10.0.0.2:2375"));
+    }
+
+    #[test]
+    fn decodes_chunked_bodies() {
+        let body = b"4\r\n[1,2\r\n2\r\n,3\r\n1\r\n]\r\n0\r\n\r\n";
+        assert_eq!(decode_chunked(body).unwrap(), b"[1,2,3]");
+    }
+}
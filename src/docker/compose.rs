@@ -0,0 +1,103 @@
+use super::{DockerContainer, DockerImage, DockerNetwork, DockerUsage, DockerVolume};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+const PROJECT_LABEL: &str = "com.docker.compose.project";
+
+/// The Compose project named in a resource's labels, given either as a JSON object or as
+/// nerdctl's `key=value,key=value` string.
+pub fn project(labels: &Value) -> Option<String> {
+    match labels {
+        Value::Object(map) => map.get(PROJECT_LABEL)?.as_str().map(String::from),
+        Value::String(text) => text
+            .split(',')
+            .find_map(|pair| pair.strip_prefix(PROJECT_LABEL)?.strip_prefix('='))
+            .map(String::from),
+        _ => None,
+    }
+}
+
+/// Everything a Compose project left behind on one runtime.
+#[derive(Clone)]
+pub struct ComposeProject {
+    pub name: String,
+    pub containers: Vec<DockerContainer>,
+    /// Images the project built, or pulled and no container outside the project uses.
+    pub images: Vec<DockerImage>,
+    pub volumes: Vec<DockerVolume>,
+    pub networks: Vec<DockerNetwork>,
+}
+
+impl ComposeProject {
+    pub fn is_running(&self) -> bool {
+        self.containers.iter().any(|container| container.running)
+    }
+
+    pub fn size(&self) -> u64 {
+        self.containers.iter().map(|c| c.size).sum::<u64>() +
+        self.volumes.iter().map(|v| v.size).sum::<u64>() +
+        self.images.iter().map(DockerImage::unique_size).sum::<u64>()
+    }
+}
+
+/// Resources grouped by their `com.docker.compose.project` label, largest project first.
+pub fn projects(usage: &DockerUsage) -> Vec<ComposeProject> {
+    let mut projects: BTreeMap<String, ComposeProject> = BTreeMap::new();
+
+    for container in &usage.containers {
+        if let Some(name) = &container.project {
+            entry(&mut projects, name).containers.push(container.clone());
+        }
+    }
+    for volume in &usage.volumes {
+        if let Some(name) = &volume.project {
+            entry(&mut projects, name).volumes.push(volume.clone());
+        }
+    }
+    for network in &usage.networks {
+        if let Some(name) = &network.project {
+            entry(&mut projects, name).networks.push(network.clone());
+        }
+    }
+    for image in &usage.images {
+        let users: Vec<Option<&str>> = usage
+            .containers_using(image)
+            .map(|container| container.project.as_deref())
+            .collect();
+        let only_user = match users.split_first() {
+            Some((first, rest)) if rest.iter().all(|user| user == first) => *first,
+            _ => None,
+        };
+        if let Some(name) = image.project.as_deref().or(only_user) {
+            entry(&mut projects, name).images.push(image.clone());
+        }
+    }
+
+    let mut projects: Vec<ComposeProject> = projects.into_values().collect();
+    projects.sort_by_key(|project| std::cmp::Reverse(project.size()));
+    projects
+}
+
+fn entry<'a>(projects: &'a mut BTreeMap<String, ComposeProject>, name: &str) -> &'a mut ComposeProject {
+    projects.entry(name.to_string()).or_insert_with(|| ComposeProject {
+        name: name.to_string(),
+        containers: Vec::new(),
+        images: Vec::new(),
+        volumes: Vec::new(),
+        networks: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reads_the_project_label() {
+        assert_eq!(project(&json!({PROJECT_LABEL: "shop", "other": "x"})).as_deref(), Some("shop"));
+        assert_eq!(project(&json!("a=b,com.docker.compose.project=shop")).as_deref(), Some("shop"));
+        assert_eq!(project(&json!({"other": "x"})), None);
+        assert_eq!(project(&Value::Null), None);
+    }
+}
@@ -0,0 +1,81 @@
+use super::DockerClient;
+use std::{
+    env, fs,
+    io::{BufRead, BufReader, Write},
+    os::unix::net::UnixListener,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+};
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// A Docker daemon stand-in listening on a temporary Unix socket. Each route maps a
+/// `"METHOD /path"` prefix of the request line to a status code and JSON body.
+pub struct FakeDaemon {
+    dir: PathBuf,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl FakeDaemon {
+    pub fn start(routes: Vec<(&'static str, u16, &'static str)>) -> Self {
+        let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+        let dir = env::temp_dir().join(format!("macmon-docker-{}-{id}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let listener = UnixListener::bind(dir.join("docker.sock")).unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let seen = Arc::clone(&requests);
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else {
+                    break;
+                };
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                loop {
+                    let mut header = String::new();
+                    if reader.read_line(&mut header).unwrap() == 0 || header == "\r\n" {
+                        break;
+                    }
+                }
+                seen.lock().unwrap().push(request_line.trim().to_string());
+
+                let (status, body) = routes
+                    .iter()
+                    .find(|(route, _, _)| request_line.starts_with(route))
+                    .map(|(_, status, body)| (*status, *body))
+                    .unwrap_or((404, r#"{"message": "no such route"}"#));
+
+                // Chunked like the real daemon, split in two to exercise reassembly.
+                let (first, second) = body.split_at(body.len() / 2);
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 {status} Fake\r\nContent-Type: application/json\r\nTransfer-Encoding: chunked\r\n\r\n{:x}\r\n{first}\r\n{:x}\r\n{second}\r\n0\r\n\r\n",
+                    first.len(),
+                    second.len()
+                );
+            }
+        });
+
+        Self { dir, requests }
+    }
+
+    pub fn client(&self) -> DockerClient {
+        DockerClient::new(self.dir.join("docker.sock"))
+    }
+
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl Drop for FakeDaemon {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
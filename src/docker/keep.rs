@@ -0,0 +1,108 @@
+use super::DockerImage;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Repository patterns whose images are never offered for removal, stored one per line in
+/// `~/.config/macmon/docker-keep`. `*` matches any run of characters, so `node` keeps the
+/// official Node images and `ghcr.io/acme/*` keeps everything from one organisation.
+#[derive(Clone, Default)]
+pub struct KeepList {
+    path: PathBuf,
+    patterns: Vec<String>,
+}
+
+impl KeepList {
+    pub fn load(home: &Path) -> Self {
+        let path = home.join(".config/macmon/docker-keep");
+        let patterns = fs::read_to_string(&path)
+            .unwrap_or_default()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(String::from)
+            .collect();
+        Self { path, patterns }
+    }
+
+    pub fn keeps(&self, image: &DockerImage) -> bool {
+        image
+            .repositories()
+            .any(|repo| self.patterns.iter().any(|pattern| glob_match(pattern, repo)))
+    }
+
+    /// Adds the image's first repository to the list, or removes every pattern that keeps
+    /// the image, then saves the list.
+    pub fn toggle(&mut self, image: &DockerImage) -> io::Result<()> {
+        if self.keeps(image) {
+            self.patterns
+                .retain(|pattern| !image.repositories().any(|repo| glob_match(pattern, repo)));
+        } else if let Some(repo) = image.repositories().next() {
+            self.patterns.push(repo.to_string());
+        }
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut contents = self.patterns.join("\n");
+        contents.push('\n');
+        fs::write(&self.path, contents)
+    }
+}
+
+pub(super) fn glob_match(pattern: &str, text: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == text,
+        Some((prefix, rest)) => {
+            let Some(text) = text.strip_prefix(prefix) else {
+                return false;
+            };
+            (0..=text.len())
+                .filter(|&i| text.is_char_boundary(i))
+                .any(|i| glob_match(rest, &text[i..]))
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docker::test_image;
+    use std::env;
+
+    fn image(tags: &[&str]) -> DockerImage {
+        test_image("sha256:a", tags)
+    }
+
+    #[test]
+    fn matches_repositories_against_patterns() {
+        let dir = env::temp_dir().join(format!("macmon-keep-{}", std::process::id()));
+        fs::create_dir_all(dir.join(".config/macmon")).unwrap();
+        fs::write(dir.join(".config/macmon/docker-keep"), "# base images\nnode\nlocalhost:5000/acme/*\n").unwrap();
+
+        let keep = KeepList::load(&dir);
+
+        assert!(keep.keeps(&image(&["node:20-alpine"])));
+        assert!(keep.keeps(&image(&["localhost:5000/acme/api:1.2"])));
+        assert!(!keep.keeps(&image(&["nodejs/other:1"])));
+        assert!(!keep.keeps(&image(&[])));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn toggling_adds_and_removes_the_repository() {
+        let dir = env::temp_dir().join(format!("macmon-keep-toggle-{}", std::process::id()));
+        let mut keep = KeepList::load(&dir);
+        let postgres = image(&["postgres:16"]);
+
+        keep.toggle(&postgres).unwrap();
+        assert!(KeepList::load(&dir).keeps(&postgres));
+
+        keep.toggle(&postgres).unwrap();
+        assert!(!KeepList::load(&dir).keeps(&postgres));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
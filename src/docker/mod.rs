@@ -0,0 +1,612 @@
+mod client;
+pub mod compose;
+#[cfg(test)]
+mod fake_daemon;
+mod keep;
+pub mod retention;
+pub mod runtime;
+
+pub use client::DockerClient;
+pub use keep::KeepList;
+
+use chrono::DateTime;
+use serde::{Deserialize, de::IgnoredAny};
+use serde_json::Value;
+use std::{
+    collections::{HashMap, HashSet},
+    io,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Networks every daemon creates, which prune never removes. Podman names its bridge `podman`.
+const DEFAULT_NETWORKS: [&str; 4] = ["bridge", "host", "none", "podman"];
+
+#[derive(Clone)]
+pub struct DockerImage {
+    pub id: String,
+    pub tags: Vec<String>,
+    /// Total size of the image, including layers shared with other images.
+    pub size: u64,
+    /// Bytes in layers this image shares with at least one other image.
+    pub shared_size: u64,
+    pub created: SystemTime,
+    /// Number of containers, running or stopped, using the image.
+    pub containers: u64,
+    /// The Compose project that built the image, if any.
+    pub project: Option<String>,
+}
+
+impl DockerImage {
+    /// The first tag, or `<none>:<none>` for untagged images.
+    pub fn name(&self) -> &str {
+        self.tags.first().map_or("<none>:<none>", String::as_str)
+    }
+
+    /// Bytes only this image holds, freed when it alone is removed.
+    pub fn unique_size(&self) -> u64 {
+        self.size.saturating_sub(self.shared_size)
+    }
+
+    /// Repository names of the image's tags, without the tag: `ghcr.io/org/app:1` -> `ghcr.io/org/app`.
+    pub fn repositories(&self) -> impl Iterator<Item = &str> {
+        self.tags.iter().map(|tag| match tag.rsplit_once(':') {
+            Some((repo, tag)) if !tag.contains('/') => repo,
+            _ => tag.as_str(),
+        })
+    }
+}
+
+/// An image with `tags` and no size, created at the epoch, for tests to adjust.
+#[cfg(test)]
+fn test_image(id: &str, tags: &[&str]) -> DockerImage {
+    DockerImage {
+        id: id.to_string(),
+        tags: tags.iter().map(|t| t.to_string()).collect(),
+        size: 0,
+        shared_size: 0,
+        created: SystemTime::UNIX_EPOCH,
+        containers: 0,
+        project: None,
+    }
+}
+
+#[derive(Clone)]
+pub struct DockerContainer {
+    pub id: String,
+    pub name: String,
+    pub image: String,
+    image_id: String,
+    pub running: bool,
+    /// Size of the container's writable layer.
+    pub size: u64,
+    networks: Vec<String>,
+    pub project: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct DockerVolume {
+    pub name: String,
+    pub size: u64,
+    /// Number of containers mounting the volume.
+    pub containers: u64,
+    pub project: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct DockerNetwork {
+    pub name: String,
+    pub project: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct BuildCacheRecord {
+    pub description: String,
+    pub size: u64,
+    pub in_use: bool,
+    pub last_used: Option<SystemTime>,
+}
+
+impl BuildCacheRecord {
+    fn older_than(&self, hours: u64) -> bool {
+        let age = self.last_used.and_then(|t| t.elapsed().ok());
+        age.is_none_or(|age| age >= Duration::from_secs(hours * 3600))
+    }
+}
+
+/// Everything the daemon stores, as reported by `docker system df -v`, plus its networks.
+#[derive(Clone, Default)]
+pub struct DockerUsage {
+    pub images: Vec<DockerImage>,
+    pub layers_size: u64,
+    pub containers: Vec<DockerContainer>,
+    pub volumes: Vec<DockerVolume>,
+    pub build_cache: Vec<BuildCacheRecord>,
+    pub networks: Vec<DockerNetwork>,
+}
+
+impl DockerUsage {
+    /// Bytes an image prune would free: every layer except the ones held only by images a
+    /// container uses. Computed the same way as the RECLAIMABLE column of `docker system df`.
+    pub fn reclaimable_images(&self) -> u64 {
+        let used: u64 = self
+            .images
+            .iter()
+            .filter(|image| image.containers > 0)
+            .map(DockerImage::unique_size)
+            .sum();
+        self.layers_size.saturating_sub(used)
+    }
+
+    /// Containers, running or stopped, created from `image`.
+    pub fn containers_using<'a>(&'a self, image: &'a DockerImage) -> impl Iterator<Item = &'a DockerContainer> {
+        self.containers.iter().filter(move |container| container.image_id == image.id)
+    }
+
+    pub fn stopped_containers(&self) -> impl Iterator<Item = &DockerContainer> {
+        self.containers.iter().filter(|container| !container.running)
+    }
+
+    pub fn unused_volumes(&self) -> impl Iterator<Item = &DockerVolume> {
+        self.volumes.iter().filter(|volume| volume.containers == 0)
+    }
+
+    /// Build cache records a prune with `until=<hours>h` would remove.
+    pub fn build_cache_older_than(&self, hours: u64) -> impl Iterator<Item = &BuildCacheRecord> {
+        self.build_cache
+            .iter()
+            .filter(move |record| !record.in_use && record.older_than(hours))
+    }
+
+    /// User-defined networks no container is attached to.
+    pub fn unused_networks(&self) -> Vec<&str> {
+        let attached: HashSet<&str> = self
+            .containers
+            .iter()
+            .flat_map(|container| container.networks.iter().map(String::as_str))
+            .collect();
+        self.networks
+            .iter()
+            .map(|network| network.name.as_str())
+            .filter(|name| !DEFAULT_NETWORKS.contains(name) && !attached.contains(name))
+            .collect()
+    }
+
+    pub fn sorted_images(&self, order: ImageOrder) -> Vec<&DockerImage> {
+        let mut images: Vec<&DockerImage> = self.images.iter().collect();
+        match order {
+            ImageOrder::Size => images.sort_by_key(|image| std::cmp::Reverse(image.size)),
+            ImageOrder::Age => images.sort_by_key(|image| image.created),
+        }
+        images
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub enum ImageOrder {
+    #[default]
+    Size,
+    /// Oldest first.
+    Age,
+}
+
+impl ImageOrder {
+    pub fn toggle(self) -> Self {
+        match self {
+            Self::Size => Self::Age,
+            Self::Age => Self::Size,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct SystemDf {
+    #[serde(default)]
+    layers_size: i64,
+    #[serde(default)]
+    images: Option<Vec<ImageSummary>>,
+    #[serde(default)]
+    containers: Option<Vec<ContainerSummary>>,
+    #[serde(default)]
+    volumes: Option<Vec<VolumeSummary>>,
+    #[serde(default)]
+    build_cache: Option<Vec<BuildCacheSummary>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ImageSummary {
+    id: String,
+    repo_tags: Option<Vec<String>>,
+    size: i64,
+    /// -1 when the daemon did not compute it.
+    #[serde(default)]
+    shared_size: i64,
+    created: i64,
+    #[serde(default)]
+    containers: i64,
+    #[serde(default)]
+    labels: Value,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ContainerSummary {
+    id: String,
+    #[serde(default)]
+    names: Vec<String>,
+    #[serde(default)]
+    image: String,
+    #[serde(default, rename = "ImageID")]
+    image_id: String,
+    #[serde(default)]
+    state: String,
+    #[serde(default)]
+    size_rw: i64,
+    network_settings: Option<NetworkSettings>,
+    #[serde(default)]
+    labels: Value,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct NetworkSettings {
+    #[serde(default)]
+    networks: Option<HashMap<String, Value>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct VolumeSummary {
+    name: String,
+    usage_data: Option<VolumeUsage>,
+    #[serde(default)]
+    labels: Value,
+}
+
+/// Size and ref count are -1 when the daemon did not compute them.
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct VolumeUsage {
+    size: i64,
+    ref_count: i64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct BuildCacheSummary {
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    r#type: String,
+    #[serde(default)]
+    size: i64,
+    #[serde(default)]
+    in_use: bool,
+    last_used_at: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct NetworkSummary {
+    name: String,
+    #[serde(default)]
+    labels: Value,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct PruneResponse {
+    #[serde(default)]
+    space_reclaimed: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct NetworksPruneResponse {
+    networks_deleted: Option<Vec<String>>,
+}
+
+/// Images, containers, volumes and build cache with their byte sizes, one entry per image ID,
+/// along with the daemon's networks.
+pub fn disk_usage(client: &DockerClient) -> io::Result<DockerUsage> {
+    let df: SystemDf = client.get("/system/df")?;
+
+    let images = df
+        .images
+        .unwrap_or_default()
+        .into_iter()
+        .map(|summary| DockerImage {
+            id: summary.id,
+            tags: summary
+                .repo_tags
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|tag| tag != "<none>:<none>")
+                .collect(),
+            size: summary.size.max(0) as u64,
+            shared_size: summary.shared_size.max(0) as u64,
+            created: UNIX_EPOCH + Duration::from_secs(summary.created.max(0) as u64),
+            containers: summary.containers.max(0) as u64,
+            project: compose::project(&summary.labels),
+        })
+        .collect();
+
+    let containers = df
+        .containers
+        .unwrap_or_default()
+        .into_iter()
+        .map(|summary| DockerContainer {
+            project: compose::project(&summary.labels),
+            id: summary.id,
+            name: summary
+                .names
+                .first()
+                .map(|name| name.trim_start_matches('/').to_string())
+                .unwrap_or_default(),
+            image: summary.image,
+            image_id: summary.image_id,
+            running: summary.state == "running",
+            size: summary.size_rw.max(0) as u64,
+            networks: summary
+                .network_settings
+                .and_then(|settings| settings.networks)
+                .map(|networks| networks.into_keys().collect())
+                .unwrap_or_default(),
+        })
+        .collect();
+
+    let volumes = df
+        .volumes
+        .unwrap_or_default()
+        .into_iter()
+        .map(|summary| {
+            let (size, containers) = summary
+                .usage_data
+                .map_or((0, 0), |usage| (usage.size.max(0) as u64, usage.ref_count.max(0) as u64));
+            DockerVolume { project: compose::project(&summary.labels), name: summary.name, size, containers }
+        })
+        .collect();
+
+    let build_cache = df
+        .build_cache
+        .unwrap_or_default()
+        .into_iter()
+        .map(|summary| BuildCacheRecord {
+            description: if summary.description.is_empty() { summary.r#type } else { summary.description },
+            size: summary.size.max(0) as u64,
+            in_use: summary.in_use,
+            last_used: summary
+                .last_used_at
+                .and_then(|at| DateTime::parse_from_rfc3339(&at).ok())
+                .map(SystemTime::from),
+        })
+        .collect();
+
+    let networks: Vec<NetworkSummary> = client.get("/networks")?;
+
+    Ok(DockerUsage {
+        images,
+        layers_size: df.layers_size.max(0) as u64,
+        containers,
+        volumes,
+        build_cache,
+        networks: networks
+            .into_iter()
+            .map(|network| DockerNetwork { project: compose::project(&network.labels), name: network.name })
+            .collect(),
+    })
+}
+
+/// Removes a tag, or an image by ID. Never forces removal, so images backing a container are
+/// left alone.
+pub fn remove_image(client: &DockerClient, name: &str) -> io::Result<()> {
+    client.delete::<IgnoredAny>(&format!("/images/{name}"))?;
+    Ok(())
+}
+
+/// Removes a stopped container, a volume or a network: `kind` is `containers`, `volumes` or
+/// `networks`.
+pub fn remove_resource(client: &DockerClient, kind: &str, name: &str) -> io::Result<()> {
+    client.delete::<IgnoredAny>(&format!("/{kind}/{name}"))?;
+    Ok(())
+}
+
+/// Removes every stopped container and returns the bytes reclaimed.
+pub fn prune_containers(client: &DockerClient) -> io::Result<u64> {
+    let response: PruneResponse = client.post("/containers/prune")?;
+    Ok(response.space_reclaimed)
+}
+
+/// Removes every volume no container mounts, named ones included, and returns the bytes
+/// reclaimed.
+pub fn prune_volumes(client: &DockerClient) -> io::Result<u64> {
+    let response: PruneResponse = client.post(&format!("/volumes/prune?filters={}", filter("all", "true")))?;
+    Ok(response.space_reclaimed)
+}
+
+/// Removes build cache not used in the last `hours` hours and returns the bytes reclaimed.
+pub fn prune_build_cache(client: &DockerClient, hours: u64) -> io::Result<u64> {
+    let until = format!("{hours}h");
+    let response: PruneResponse = client.post(&format!("/build/prune?filters={}", filter("until", &until)))?;
+    Ok(response.space_reclaimed)
+}
+
+/// Removes every user-defined network no container is attached to and returns their names.
+pub fn prune_networks(client: &DockerClient) -> io::Result<Vec<String>> {
+    let response: NetworksPruneResponse = client.post("/networks/prune")?;
+    Ok(response.networks_deleted.unwrap_or_default())
+}
+
+/// A URL-encoded `filters` value holding a single `key=value` filter.
+fn filter(key: &str, value: &str) -> String {
+    let json = format!("{{\"{key}\":[\"{value}\"]}}");
+    json.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fake_daemon::FakeDaemon;
+
+    const SYSTEM_DF: &str = r#"{
+        "LayersSize": 3000,
+        "Images": [
+            {"Id": "sha256:a", "RepoTags": ["app:1", "app:latest"], "Size": 2000, "SharedSize": 500, "Created": 1700000000, "Containers": 1},
+            {"Id": "sha256:b", "RepoTags": ["<none>:<none>"], "Size": 800, "SharedSize": 500, "Created": 1600000000, "Containers": 0},
+            {"Id": "sha256:c", "RepoTags": null, "Size": 700, "SharedSize": -1, "Created": 1650000000, "Containers": 0}
+        ],
+        "Containers": [
+            {"Id": "c1", "Names": ["/web"], "Image": "app:1", "ImageID": "sha256:a", "State": "running", "SizeRw": 10,
+             "NetworkSettings": {"Networks": {"frontend": {}}}, "Labels": {"com.docker.compose.project": "shop"}},
+            {"Id": "c2", "Names": ["/old-job"], "Image": "app:1", "ImageID": "sha256:a", "State": "exited", "SizeRw": 4000,
+             "NetworkSettings": {"Networks": {"bridge": {}}}, "Labels": {"com.docker.compose.project": "shop"}}
+        ],
+        "Volumes": [
+            {"Name": "db-data", "UsageData": {"Size": 9000, "RefCount": 0},
+             "Labels": {"com.docker.compose.project": "shop"}},
+            {"Name": "cache", "UsageData": {"Size": 100, "RefCount": 1}}
+        ],
+        "BuildCache": [
+            {"Type": "regular", "Description": "mount / from exec /bin/sh", "Size": 5000, "InUse": false,
+             "LastUsedAt": "2023-01-01T00:00:00Z"},
+            {"Type": "source.local", "Description": "", "Size": 200, "InUse": true,
+             "LastUsedAt": "2023-01-01T00:00:00Z"}
+        ]
+    }"#;
+
+    const NETWORKS: &str = r#"[{"Name": "bridge"}, {"Name": "host"}, {"Name": "frontend", "Labels": {"com.docker.compose.project": "shop"}}, {"Name": "stale"}]"#;
+
+    fn daemon() -> FakeDaemon {
+        FakeDaemon::start(vec![("GET /system/df", 200, SYSTEM_DF), ("GET /networks", 200, NETWORKS)])
+    }
+
+    fn daemon_usage() -> DockerUsage {
+        disk_usage(&daemon().client()).unwrap()
+    }
+
+    #[test]
+    fn reports_byte_sizes_per_image() {
+        let daemon = daemon();
+
+        let usage = disk_usage(&daemon.client()).unwrap();
+
+        let names: Vec<&str> = usage.images.iter().map(DockerImage::name).collect();
+        assert_eq!(names, ["app:1", "<none>:<none>", "<none>:<none>"]);
+        assert_eq!(usage.images[0].tags.len(), 2);
+        assert_eq!(usage.images[0].unique_size(), 1500);
+        assert_eq!(usage.images[1].unique_size(), 300);
+        assert_eq!(usage.images[2].unique_size(), 700);
+        assert_eq!(usage.images[0].created, UNIX_EPOCH + Duration::from_secs(1_700_000_000));
+    }
+
+    #[test]
+    fn reclaimable_excludes_layers_only_used_images_hold() {
+        let daemon = daemon();
+
+        let usage = disk_usage(&daemon.client()).unwrap();
+
+        assert_eq!(usage.reclaimable_images(), 1500);
+    }
+
+    #[test]
+    fn sorts_by_size_or_age() {
+        let daemon = daemon();
+        let usage = disk_usage(&daemon.client()).unwrap();
+
+        let sizes: Vec<u64> = usage.sorted_images(ImageOrder::Size).iter().map(|i| i.size).collect();
+        assert_eq!(sizes, [2000, 800, 700]);
+
+        let sizes: Vec<u64> = usage.sorted_images(ImageOrder::Age).iter().map(|i| i.size).collect();
+        assert_eq!(sizes, [800, 700, 2000]);
+    }
+
+    #[test]
+    fn reports_containers_volumes_build_cache_and_networks() {
+        let daemon = daemon();
+
+        let usage = disk_usage(&daemon.client()).unwrap();
+
+        let stopped: Vec<&str> = usage.stopped_containers().map(|c| c.name.as_str()).collect();
+        assert_eq!(stopped, ["old-job"]);
+        let unused: Vec<(&str, u64)> = usage.unused_volumes().map(|v| (v.name.as_str(), v.size)).collect();
+        assert_eq!(unused, [("db-data", 9000)]);
+        let old: Vec<u64> = usage.build_cache_older_than(24).map(|r| r.size).collect();
+        assert_eq!(old, [5000]);
+        assert_eq!(usage.build_cache[1].description, "source.local");
+        assert_eq!(usage.unused_networks(), ["stale"]);
+    }
+
+    #[test]
+    fn groups_resources_by_compose_project() {
+        let usage = daemon_usage();
+
+        let projects = compose::projects(&usage);
+
+        assert_eq!(projects.len(), 1);
+        let shop = &projects[0];
+        assert_eq!(shop.name, "shop");
+        assert_eq!(shop.containers.len(), 2);
+        assert!(shop.is_running());
+        let images: Vec<&str> = shop.images.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(images, ["sha256:a"]);
+        assert_eq!(shop.volumes[0].name, "db-data");
+        assert_eq!(shop.networks[0].name, "frontend");
+        assert_eq!(shop.size(), 10 + 4000 + 9000 + 1500);
+    }
+
+    #[test]
+    fn deletes_resources_with_no_content_responses() {
+        let daemon = FakeDaemon::start(vec![("DELETE /volumes/db-data", 204, "")]);
+
+        remove_resource(&daemon.client(), "volumes", "db-data").unwrap();
+        assert!(daemon.requests()[0].starts_with("DELETE /volumes/db-data "));
+    }
+
+    #[test]
+    fn prunes_build_cache_older_than_given_hours() {
+        let daemon = FakeDaemon::start(vec![("POST /build/prune", 200, r#"{"CachesDeleted": ["a"], "SpaceReclaimed": 5000}"#)]);
+
+        assert_eq!(prune_build_cache(&daemon.client(), 48).unwrap(), 5000);
+        assert!(daemon.requests()[0].contains("%7B%22until%22%3A%5B%2248h%22%5D%7D"));
+    }
+
+    #[test]
+    fn prunes_networks_and_reports_their_names() {
+        let daemon = FakeDaemon::start(vec![("POST /networks/prune", 200, r#"{"NetworksDeleted": ["stale"]}"#)]);
+
+        assert_eq!(prune_networks(&daemon.client()).unwrap(), ["stale"]);
+    }
+
+    #[test]
+    fn links_images_to_their_containers() {
+        let usage = daemon_usage();
+
+        let users: Vec<&str> = usage.containers_using(&usage.images[0]).map(|c| c.name.as_str()).collect();
+        assert_eq!(users, ["web", "old-job"]);
+        assert_eq!(usage.containers_using(&usage.images[1]).count(), 0);
+    }
+
+    #[test]
+    fn surfaces_daemon_errors() {
+        let daemon = FakeDaemon::start(vec![(
+            "GET /system/df",
+            500,
+            r#"{"message": "daemon is shutting down"}"#,
+        )]);
+
+        let err = disk_usage(&daemon.client()).err().unwrap();
+        assert!(err.to_string().contains("daemon is shutting down"));
+    }
+
+    #[test]
+    fn fails_when_socket_is_missing() {
+        let client = DockerClient::new("/nonexistent/docker.sock");
+        assert!(disk_usage(&client).is_err());
+    }
+}
@@ -0,0 +1,168 @@
+use super::{keep::glob_match, runtime::Runtime, DockerImage, DockerUsage, KeepList};
+use std::{collections::HashMap, io};
+
+/// Which tags survive a retention run: the newest `keep_newest` tags of each repository, plus
+/// any tag matching one of `keep_tags`.
+#[derive(Clone)]
+pub struct RetentionPolicy {
+    pub keep_newest: usize,
+    pub keep_tags: Vec<String>,
+    pub remove_dangling: bool,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            keep_newest: 3,
+            keep_tags: vec!["latest".to_string(), "stable".to_string()],
+            remove_dangling: true,
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct RetentionPlan {
+    /// Tags to remove, as `repository:tag`.
+    pub tags: Vec<String>,
+    /// IDs of untagged images to remove.
+    pub dangling: Vec<String>,
+    /// Bytes freed by images losing every tag, or dangling.
+    pub bytes: u64,
+}
+
+impl RetentionPlan {
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.dangling.is_empty()
+    }
+}
+
+/// What `policy` would remove. Images used by a container or matched by the keep-list count
+/// towards the newest tags of their repository but are never removed.
+pub fn plan(usage: &DockerUsage, policy: &RetentionPolicy, keep: &KeepList) -> RetentionPlan {
+    let protected = |image: &DockerImage| {
+        image.containers > 0 || usage.containers_using(image).next().is_some() || keep.keeps(image)
+    };
+
+    let mut repositories: HashMap<&str, Vec<(&str, &DockerImage)>> = HashMap::new();
+    for image in &usage.images {
+        for (tag, repo) in image.tags.iter().zip(image.repositories()) {
+            repositories.entry(repo).or_default().push((tag, image));
+        }
+    }
+
+    let mut plan = RetentionPlan::default();
+    let mut untagged: HashMap<&str, usize> = HashMap::new();
+    for (repo, mut tags) in repositories {
+        tags.sort_by(|a, b| b.1.created.cmp(&a.1.created).then(a.0.cmp(b.0)));
+        for (full_tag, image) in tags.into_iter().skip(policy.keep_newest) {
+            if protected(image) {
+                continue;
+            }
+            let tag = full_tag.strip_prefix(repo).unwrap_or(full_tag).trim_start_matches(':');
+            if policy.keep_tags.iter().any(|pattern| glob_match(pattern, tag)) {
+                continue;
+            }
+            plan.tags.push(full_tag.to_string());
+            *untagged.entry(image.id.as_str()).or_default() += 1;
+        }
+    }
+    plan.tags.sort();
+
+    for image in &usage.images {
+        if untagged.get(image.id.as_str()) == Some(&image.tags.len()) {
+            plan.bytes += image.unique_size();
+        }
+        if policy.remove_dangling && image.tags.is_empty() && !protected(image) {
+            plan.dangling.push(image.id.clone());
+            plan.bytes += image.unique_size();
+        }
+    }
+
+    plan
+}
+
+/// Removes everything in `plan`, returning each tag or image ID that failed with its error.
+pub fn apply(runtime: &Runtime, plan: &RetentionPlan) -> Vec<(String, io::Error)> {
+    plan.tags
+        .iter()
+        .chain(&plan.dangling)
+        .filter_map(|name| runtime.remove_reference(name).err().map(|err| (name.clone(), err)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docker::{fake_daemon::FakeDaemon, test_image};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn image(id: &str, tags: &[&str], created: u64) -> DockerImage {
+        DockerImage {
+            size: 100,
+            created: UNIX_EPOCH + Duration::from_secs(created),
+            ..test_image(id, tags)
+        }
+    }
+
+    fn usage() -> DockerUsage {
+        DockerUsage {
+            images: vec![
+                image("sha256:1", &["ci/app:build-1"], 1),
+                image("sha256:2", &["ci/app:build-2", "ci/app:stable"], 2),
+                image("sha256:3", &["ci/app:build-3"], 3),
+                image("sha256:4", &["ci/app:build-4", "ci/app:latest"], 4),
+                image("sha256:5", &["postgres:16"], 1),
+                image("sha256:6", &[], 1),
+                image("sha256:7", &["ci/worker:build-1"], 1),
+                image("sha256:8", &["ci/worker:build-2"], 2),
+            ],
+            ..DockerUsage::default()
+        }
+    }
+
+    #[test]
+    fn keeps_newest_tags_and_patterns_per_repository() {
+        let policy = RetentionPolicy { keep_newest: 2, ..RetentionPolicy::default() };
+
+        let plan = plan(&usage(), &policy, &KeepList::default());
+
+        assert_eq!(plan.tags, ["ci/app:build-1", "ci/app:build-2", "ci/app:build-3"]);
+        assert_eq!(plan.dangling, ["sha256:6"]);
+        // build-1 and build-3 lose their only tag; build-2 keeps `stable`.
+        assert_eq!(plan.bytes, 300);
+    }
+
+    #[test]
+    fn skips_images_in_use() {
+        let mut usage = usage();
+        usage.images[0].containers = 1;
+        usage.images[5].containers = 1;
+        usage.images[2].containers = 1;
+        let policy = RetentionPolicy { keep_newest: 0, keep_tags: Vec::new(), remove_dangling: true };
+
+        let plan = plan(&usage, &policy, &KeepList::default());
+
+        assert!(!plan.tags.iter().any(|t| t == "ci/app:build-1" || t == "ci/app:build-3"));
+        assert!(plan.tags.iter().any(|t| t == "postgres:16"));
+        assert!(plan.dangling.is_empty());
+    }
+
+    #[test]
+    fn applies_the_plan_and_reports_failures() {
+        let daemon = FakeDaemon::start(vec![
+            ("DELETE /images/ci/app:build-1", 409, r#"{"message": "conflict"}"#),
+            ("DELETE /images/", 200, r#"[{"Untagged": "x"}]"#),
+        ]);
+        let plan = RetentionPlan {
+            tags: vec!["ci/app:build-1".to_string(), "ci/app:build-2".to_string()],
+            dangling: vec!["sha256:6".to_string()],
+            bytes: 0,
+        };
+
+        let failures = apply(&Runtime::api("Docker", daemon.client()), &plan);
+
+        assert_eq!(daemon.requests().len(), 3);
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].0, "ci/app:build-1");
+    }
+}
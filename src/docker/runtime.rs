@@ -0,0 +1,713 @@
+use super::{
+    client::unix_socket,
+    compose::{self, ComposeProject},
+    DockerClient, DockerContainer, DockerImage, DockerNetwork, DockerUsage, DockerVolume, KeepList,
+};
+use crate::detectors::{calculate_dir_size, is_installed};
+use chrono::DateTime;
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    env, fs, io,
+    path::{Path, PathBuf},
+    process::Command,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+const PODMAN_SOCKETS: [&str; 3] = [
+    "/run/podman/podman.sock",
+    ".local/share/containers/podman/machine/podman.sock",
+    ".local/share/containers/podman/machine/qemu/podman.sock",
+];
+
+/// A container engine macmon can report on and clean up.
+#[derive(Clone)]
+pub struct Runtime {
+    pub name: &'static str,
+    backend: Backend,
+}
+
+#[derive(Clone)]
+enum Backend {
+    /// The Docker Engine API, spoken by Docker and by Podman's compatibility socket.
+    Api(DockerClient),
+    /// A Docker-like CLI with JSON output, for Podman without its socket service and nerdctl.
+    Cli(Cli),
+    /// A daemon macmon cannot reach, such as a `tcp://` `DOCKER_HOST`, kept so it is reported.
+    Unsupported(String),
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Cli {
+    Podman,
+    Nerdctl,
+}
+
+impl Cli {
+    fn program(self) -> &'static str {
+        match self {
+            Self::Podman => "podman",
+            Self::Nerdctl => "nerdctl",
+        }
+    }
+
+    fn network_names(self) -> io::Result<Vec<String>> {
+        let networks: Vec<NamedNetwork> = match self {
+            Self::Podman => run_json("podman", &["network", "ls", "--format", "json"])?,
+            Self::Nerdctl => run_json_lines("nerdctl", &["network", "ls", "--format", "{{json .}}"])?,
+        };
+        Ok(networks.into_iter().map(|network| network.name).collect())
+    }
+}
+
+/// A runtime with what it stores, or why it could not be asked.
+#[derive(Clone)]
+pub struct RuntimeUsage {
+    pub runtime: Runtime,
+    pub usage: DockerUsage,
+    pub error: Option<String>,
+}
+
+/// Every runtime found on this machine: Docker's socket, Podman's socket or CLI, and nerdctl
+/// (containerd). A socket shared by two runtimes, such as `podman-docker`, is reported once.
+pub fn detect(home: &Path) -> Vec<Runtime> {
+    let mut runtimes = Vec::new();
+
+    let docker = DockerClient::from_env(home).unwrap_or_else(|host| {
+        let reason = format!("unsupported Docker host {host}: only unix:// sockets can be reached");
+        runtimes.push(Runtime { name: "Docker", backend: Backend::Unsupported(reason) });
+        None
+    });
+    let docker_socket = docker.as_ref().and_then(|client| fs::canonicalize(client.socket()).ok());
+    if let Some(client) = docker {
+        runtimes.push(Runtime::api("Docker", client));
+    }
+
+    let podman_socket = podman_socket(home);
+    let shared = docker_socket.is_some() &&
+        podman_socket.as_ref().and_then(|socket| fs::canonicalize(socket).ok()) == docker_socket;
+    if shared {
+        // Docker's socket is Podman's, already reported above.
+    } else if let Some(socket) = podman_socket {
+        runtimes.push(Runtime::api("Podman", DockerClient::new(socket)));
+    } else if is_installed("podman") {
+        runtimes.push(Runtime { name: "Podman", backend: Backend::Cli(Cli::Podman) });
+    }
+
+    if is_installed("nerdctl") {
+        runtimes.push(Runtime { name: "nerdctl", backend: Backend::Cli(Cli::Nerdctl) });
+    }
+
+    runtimes
+}
+
+/// Asks every detected runtime what it stores.
+pub fn scan(home: &Path) -> Vec<RuntimeUsage> {
+    detect(home)
+        .into_iter()
+        .map(|runtime| match runtime.disk_usage() {
+            Ok(usage) => RuntimeUsage { runtime, usage, error: None },
+            Err(err) => RuntimeUsage { runtime, usage: DockerUsage::default(), error: Some(err.to_string()) },
+        })
+        .collect()
+}
+
+fn podman_socket(home: &Path) -> Option<PathBuf> {
+    if let Ok(host) = env::var("CONTAINER_HOST") {
+        return unix_socket(&host);
+    }
+
+    let runtime_dir = env::var_os("XDG_RUNTIME_DIR").map(|dir| PathBuf::from(dir).join("podman/podman.sock"));
+    runtime_dir
+        .into_iter()
+        .chain(PODMAN_SOCKETS.iter().map(|socket| home.join(socket)))
+        .find(|socket| socket.exists())
+}
+
+impl Runtime {
+    pub fn api(name: &'static str, client: DockerClient) -> Self {
+        Self { name, backend: Backend::Api(client) }
+    }
+
+    pub fn disk_usage(&self) -> io::Result<DockerUsage> {
+        match &self.backend {
+            Backend::Api(client) => super::disk_usage(client),
+            Backend::Cli(Cli::Podman) => podman_usage(),
+            Backend::Cli(Cli::Nerdctl) => nerdctl_usage(),
+            Backend::Unsupported(reason) => Err(io::Error::other(reason.clone())),
+        }
+    }
+
+    /// Removes one image by untagging each of its tags, or by ID when it has none. Never
+    /// forces removal, so images backing a container are left alone.
+    pub fn remove_image(&self, image: &DockerImage) -> io::Result<()> {
+        if image.tags.is_empty() {
+            return self.remove_reference(&image.id);
+        }
+        for tag in &image.tags {
+            self.remove_reference(tag)?;
+        }
+        Ok(())
+    }
+
+    /// Removes a tag, or an image by ID, deleting the image once its last tag is gone.
+    pub fn remove_reference(&self, name: &str) -> io::Result<()> {
+        match &self.backend {
+            Backend::Api(client) => super::remove_image(client, name),
+            Backend::Cli(cli) => run(cli.program(), &["rmi", name]).map(drop),
+            Backend::Unsupported(reason) => Err(io::Error::other(reason.clone())),
+        }
+    }
+
+    /// Removes a stopped Compose project's containers, networks, volumes and the images only
+    /// it uses, like `docker compose down --volumes --rmi all`. Images on the keep-list stay.
+    /// Returns each resource that could not be removed.
+    pub fn tear_down(&self, project: &ComposeProject, keep: &KeepList) -> Vec<(String, io::Error)> {
+        if project.is_running() {
+            let err = io::Error::other("project is still running; stop it first");
+            return vec![(project.name.clone(), err)];
+        }
+
+        let mut failures = Vec::new();
+        let mut attempt = |name: &str, result: io::Result<()>| {
+            if let Err(err) = result {
+                failures.push((name.to_string(), err));
+            }
+        };
+
+        for container in &project.containers {
+            attempt(&container.name, self.remove_resource(&["rm"], "containers", &container.id));
+        }
+        for network in &project.networks {
+            attempt(&network.name, self.remove_resource(&["network", "rm"], "networks", &network.name));
+        }
+        for volume in &project.volumes {
+            attempt(&volume.name, self.remove_resource(&["volume", "rm"], "volumes", &volume.name));
+        }
+        for image in project.images.iter().filter(|image| !keep.keeps(image)) {
+            attempt(image.name(), self.remove_image(image));
+        }
+
+        failures
+    }
+
+    /// Deletes one container, network or volume, through `DELETE /<kind>/<name>` or the CLI's
+    /// `<command> <name>`.
+    fn remove_resource(&self, command: &[&str], kind: &str, name: &str) -> io::Result<()> {
+        match &self.backend {
+            Backend::Api(client) => super::remove_resource(client, kind, name),
+            Backend::Cli(cli) => {
+                let mut args = command.to_vec();
+                args.push(name);
+                run(cli.program(), &args).map(drop)
+            },
+            Backend::Unsupported(reason) => Err(io::Error::other(reason.clone())),
+        }
+    }
+
+    /// [`super::prune_containers`], or `container prune` sized from the last listing.
+    pub fn prune_containers(&self) -> io::Result<u64> {
+        match &self.backend {
+            Backend::Api(client) => super::prune_containers(client),
+            Backend::Cli(cli) => {
+                let freed = self.disk_usage()?.stopped_containers().map(|c| c.size).sum();
+                run(cli.program(), &["container", "prune", "--force"])?;
+                Ok(freed)
+            },
+            Backend::Unsupported(reason) => Err(io::Error::other(reason.clone())),
+        }
+    }
+
+    /// [`super::prune_volumes`], or `volume prune` sized from the last listing.
+    pub fn prune_volumes(&self) -> io::Result<u64> {
+        match &self.backend {
+            Backend::Api(client) => super::prune_volumes(client),
+            Backend::Cli(cli) => {
+                let freed = self.disk_usage()?.unused_volumes().map(|v| v.size).sum();
+                let args: &[&str] = match cli {
+                    Cli::Podman => &["volume", "prune", "--force"],
+                    Cli::Nerdctl => &["volume", "prune", "--all", "--force"],
+                };
+                run(cli.program(), args)?;
+                Ok(freed)
+            },
+            Backend::Unsupported(reason) => Err(io::Error::other(reason.clone())),
+        }
+    }
+
+    /// [`super::prune_build_cache`]; CLI runtimes have no build cache to prune.
+    pub fn prune_build_cache(&self, hours: u64) -> io::Result<u64> {
+        match &self.backend {
+            Backend::Api(client) => super::prune_build_cache(client, hours),
+            Backend::Cli(_) => Err(io::Error::other(format!("{} has no build cache to prune", self.name))),
+            Backend::Unsupported(reason) => Err(io::Error::other(reason.clone())),
+        }
+    }
+
+    /// [`super::prune_networks`], or `network prune` reporting the networks it made disappear.
+    pub fn prune_networks(&self) -> io::Result<Vec<String>> {
+        match &self.backend {
+            Backend::Api(client) => super::prune_networks(client),
+            Backend::Cli(cli) => {
+                let before = cli.network_names()?;
+                run(cli.program(), &["network", "prune", "--force"])?;
+                let after = cli.network_names()?;
+                Ok(before.into_iter().filter(|name| !after.contains(name)).collect())
+            },
+            Backend::Unsupported(reason) => Err(io::Error::other(reason.clone())),
+        }
+    }
+}
+
+fn run(program: &str, args: &[&str]) -> io::Result<String> {
+    let output = Command::new(program).args(args).output()?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(io::Error::other(format!("{program} {}: {}", args.join(" "), stderr.trim())))
+    }
+}
+
+fn run_json<T: DeserializeOwned>(program: &str, args: &[&str]) -> io::Result<T> {
+    parse_json(&run(program, args)?)
+}
+
+/// Output with one JSON object per line, as printed by `--format '{{json .}}'`.
+fn run_json_lines<T: DeserializeOwned>(program: &str, args: &[&str]) -> io::Result<Vec<T>> {
+    run(program, args)?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(parse_json)
+        .collect()
+}
+
+fn parse_json<T: DeserializeOwned>(text: &str) -> io::Result<T> {
+    serde_json::from_str(text).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct PodmanImage {
+    id: String,
+    names: Option<Vec<String>>,
+    #[serde(default)]
+    size: i64,
+    #[serde(default)]
+    shared_size: i64,
+    #[serde(default)]
+    created: i64,
+    #[serde(default)]
+    containers: i64,
+    #[serde(default)]
+    labels: Value,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct PodmanContainer {
+    id: String,
+    #[serde(default)]
+    names: Vec<String>,
+    #[serde(default)]
+    image: String,
+    #[serde(default, rename = "ImageID")]
+    image_id: String,
+    #[serde(default)]
+    state: String,
+    size: Option<PodmanContainerSize>,
+    networks: Option<Vec<String>>,
+    #[serde(default)]
+    labels: Value,
+}
+
+#[derive(Deserialize)]
+struct PodmanContainerSize {
+    #[serde(default, rename = "rwSize")]
+    rw_size: i64,
+}
+
+#[derive(Deserialize)]
+struct NamedVolume {
+    #[serde(alias = "Name")]
+    name: String,
+    #[serde(alias = "Mountpoint")]
+    mountpoint: Option<PathBuf>,
+    #[serde(default, alias = "Labels")]
+    labels: Value,
+}
+
+#[derive(Deserialize)]
+struct NamedNetwork {
+    #[serde(alias = "Name")]
+    name: String,
+    #[serde(default, alias = "Labels")]
+    labels: Value,
+}
+
+impl From<NamedNetwork> for DockerNetwork {
+    fn from(network: NamedNetwork) -> Self {
+        Self { project: compose::project(&network.labels), name: network.name }
+    }
+}
+
+fn podman_usage() -> io::Result<DockerUsage> {
+    let images: Vec<PodmanImage> = run_json("podman", &["images", "--all", "--format", "json"])?;
+    let containers: Vec<PodmanContainer> = run_json("podman", &["ps", "--all", "--size", "--format", "json"])?;
+    let volumes: Vec<NamedVolume> = run_json("podman", &["volume", "ls", "--format", "json"])?;
+    let dangling: Vec<NamedVolume> =
+        run_json("podman", &["volume", "ls", "--filter", "dangling=true", "--format", "json"])?;
+    let networks: Vec<NamedNetwork> = run_json("podman", &["network", "ls", "--format", "json"])?;
+
+    let images: Vec<DockerImage> = images
+        .into_iter()
+        .map(|image| DockerImage {
+            id: image.id,
+            tags: image.names.unwrap_or_default(),
+            size: image.size.max(0) as u64,
+            shared_size: image.shared_size.max(0) as u64,
+            created: UNIX_EPOCH + Duration::from_secs(image.created.max(0) as u64),
+            containers: image.containers.max(0) as u64,
+            project: compose::project(&image.labels),
+        })
+        .collect();
+
+    let containers = containers
+        .into_iter()
+        .map(|container| DockerContainer {
+            project: compose::project(&container.labels),
+            id: container.id,
+            name: container.names.first().cloned().unwrap_or_default(),
+            image: container.image,
+            image_id: container.image_id,
+            running: container.state == "running",
+            size: container.size.map_or(0, |size| size.rw_size.max(0) as u64),
+            networks: container.networks.unwrap_or_default(),
+        })
+        .collect();
+
+    Ok(DockerUsage {
+        layers_size: images.iter().map(DockerImage::unique_size).sum(),
+        images,
+        containers,
+        volumes: volumes_with_sizes(volumes, &dangling),
+        build_cache: Vec::new(),
+        networks: networks.into_iter().map(DockerNetwork::from).collect(),
+    })
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct NerdctlImage {
+    #[serde(rename = "ID")]
+    id: String,
+    repository: String,
+    tag: String,
+    #[serde(default)]
+    created_at: String,
+    #[serde(default)]
+    size: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct NerdctlContainer {
+    #[serde(rename = "ID")]
+    id: String,
+    #[serde(default)]
+    names: String,
+    #[serde(default)]
+    image: String,
+    #[serde(default)]
+    status: String,
+    #[serde(default)]
+    labels: Value,
+}
+
+fn nerdctl_usage() -> io::Result<DockerUsage> {
+    let images: Vec<NerdctlImage> = run_json_lines("nerdctl", &["images", "--format", "{{json .}}"])?;
+    let containers: Vec<NerdctlContainer> = run_json_lines("nerdctl", &["ps", "--all", "--format", "{{json .}}"])?;
+    let volumes: Vec<NamedVolume> = run_json_lines("nerdctl", &["volume", "ls", "--format", "{{json .}}"])?;
+    let dangling: Vec<NamedVolume> =
+        run_json_lines("nerdctl", &["volume", "ls", "--filter", "dangling=true", "--format", "{{json .}}"])
+            .unwrap_or_default();
+    let networks: Vec<NamedNetwork> = run_json_lines("nerdctl", &["network", "ls", "--format", "{{json .}}"])?;
+    let ids: Vec<&str> = containers.iter().map(|container| container.id.as_str()).collect();
+    let attached = if ids.is_empty() {
+        HashMap::new()
+    } else {
+        let mut args = vec!["container", "inspect"];
+        args.extend(&ids);
+        nerdctl_networks(&run("nerdctl", &args)?)?
+    };
+
+    let mut by_id: HashMap<String, DockerImage> = HashMap::new();
+    for image in images {
+        let entry = by_id.entry(image.id.clone()).or_insert_with(|| DockerImage {
+            id: image.id,
+            tags: Vec::new(),
+            size: parse_size(&image.size).unwrap_or(0),
+            shared_size: 0,
+            created: parse_created_at(&image.created_at).unwrap_or(UNIX_EPOCH),
+            containers: 0,
+            project: None,
+        });
+        if image.repository != "<none>" && image.tag != "<none>" {
+            entry.tags.push(format!("{}:{}", image.repository, image.tag));
+        }
+    }
+    let mut images: Vec<DockerImage> = by_id.into_values().collect();
+    images.sort_by(|a, b| a.id.cmp(&b.id));
+
+    // nerdctl names a container's image by reference rather than ID.
+    let containers: Vec<DockerContainer> = containers
+        .into_iter()
+        .map(|container| {
+            let reference = normalize_reference(&container.image);
+            let image_id = images
+                .iter()
+                .find(|image| image.tags.iter().any(|tag| normalize_reference(tag) == reference))
+                .map(|image| image.id.clone())
+                .unwrap_or_default();
+            DockerContainer {
+                project: compose::project(&container.labels),
+                name: container.names,
+                image: container.image,
+                image_id,
+                running: container.status.starts_with("Up"),
+                size: 0,
+                networks: attached
+                    .iter()
+                    .find(|(id, _)| id.starts_with(&container.id))
+                    .map(|(_, networks)| networks.clone())
+                    .unwrap_or_default(),
+                id: container.id,
+            }
+        })
+        .collect();
+    for image in &mut images {
+        image.containers = containers.iter().filter(|c| c.image_id == image.id).count() as u64;
+    }
+
+    Ok(DockerUsage {
+        layers_size: images.iter().map(|image| image.size).sum(),
+        images,
+        containers,
+        volumes: volumes_with_sizes(volumes, &dangling),
+        build_cache: Vec::new(),
+        networks: networks.into_iter().map(DockerNetwork::from).collect(),
+    })
+}
+
+/// The networks of each container in `nerdctl container inspect` output, keyed by full ID.
+/// nerdctl records them in its `nerdctl/networks` label; `NetworkSettings` only names
+/// interfaces (`unknown-eth0`) on older releases, so it is used only as a fallback.
+fn nerdctl_networks(inspect: &str) -> io::Result<HashMap<String, Vec<String>>> {
+    let containers: Vec<Value> = parse_json(inspect)?;
+    Ok(containers
+        .iter()
+        .filter_map(|container| {
+            let id = container["Id"].as_str()?.to_string();
+            let networks = container["Config"]["Labels"]["nerdctl/networks"]
+                .as_str()
+                .and_then(|label| serde_json::from_str(label).ok())
+                .unwrap_or_else(|| {
+                    container["NetworkSettings"]["Networks"]
+                        .as_object()
+                        .map(|networks| {
+                            networks.keys().filter(|name| !name.starts_with("unknown-")).cloned().collect()
+                        })
+                        .unwrap_or_default()
+                });
+            Some((id, networks))
+        })
+        .collect())
+}
+
+/// Volumes sized from their mount points, counting the `dangling` ones as unused.
+fn volumes_with_sizes(volumes: Vec<NamedVolume>, dangling: &[NamedVolume]) -> Vec<DockerVolume> {
+    volumes
+        .into_iter()
+        .map(|volume| DockerVolume {
+            size: volume.mountpoint.as_deref().map_or(0, calculate_dir_size),
+            containers: if dangling.iter().any(|d| d.name == volume.name) { 0 } else { 1 },
+            project: compose::project(&volume.labels),
+            name: volume.name,
+        })
+        .collect()
+}
+
+/// `docker.io/library/alpine:latest` and `alpine` name the same image.
+fn normalize_reference(reference: &str) -> String {
+    let reference = reference
+        .strip_prefix("docker.io/library/")
+        .or_else(|| reference.strip_prefix("docker.io/"))
+        .unwrap_or(reference);
+    let has_tag = reference.rsplit_once(':').is_some_and(|(_, tag)| !tag.contains('/'));
+    if has_tag {
+        reference.to_string()
+    } else {
+        format!("{reference}:latest")
+    }
+}
+
+/// Sizes as nerdctl prints them: `7.6 MiB`, `512B`, `1.2GB`.
+fn parse_size(text: &str) -> Option<u64> {
+    let text = text.trim();
+    let split = text.find(|c: char| !c.is_ascii_digit() && c != '.')?;
+    let (number, unit) = text.split_at(split);
+    let number: f64 = number.parse().ok()?;
+    let multiplier: f64 = match unit.trim() {
+        "B" => 1.0,
+        "kB" | "KB" => 1e3,
+        "MB" => 1e6,
+        "GB" => 1e9,
+        "TB" => 1e12,
+        "KiB" => 1024.0,
+        "MiB" => 1024.0 * 1024.0,
+        "GiB" => 1024.0 * 1024.0 * 1024.0,
+        "TiB" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    Some((number * multiplier) as u64)
+}
+
+/// Timestamps as nerdctl prints them: `2024-03-01 12:00:00 +0000 UTC`.
+fn parse_created_at(text: &str) -> Option<SystemTime> {
+    let without_zone_name: Vec<&str> = text.split_whitespace().take(3).collect();
+    DateTime::parse_from_str(&without_zone_name.join(" "), "%Y-%m-%d %H:%M:%S %z")
+        .ok()
+        .map(SystemTime::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docker::{fake_daemon::FakeDaemon, test_image};
+
+    #[test]
+    fn removes_an_image_tag_by_tag() {
+        let daemon = FakeDaemon::start(vec![("DELETE /images/", 200, r#"[{"Untagged": "app:1"}]"#)]);
+        let runtime = Runtime::api("Docker", daemon.client());
+
+        runtime.remove_image(&test_image("sha256:a", &["app:1", "app:latest"])).unwrap();
+
+        let requests = daemon.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[0].starts_with("DELETE /images/app:1 "));
+        assert!(requests[1].starts_with("DELETE /images/app:latest "));
+    }
+
+    #[test]
+    fn removes_untagged_images_by_id_and_reports_conflicts() {
+        let daemon = FakeDaemon::start(vec![(
+            "DELETE /images/sha256:b",
+            409,
+            r#"{"message": "image is being used by stopped container 1234"}"#,
+        )]);
+        let runtime = Runtime::api("Docker", daemon.client());
+
+        let err = runtime.remove_image(&test_image("sha256:b", &[])).err().unwrap();
+        assert!(err.to_string().contains("being used by stopped container"));
+    }
+
+    #[test]
+    fn tears_down_a_stopped_compose_project() {
+        let daemon = FakeDaemon::start(vec![
+            ("DELETE /images/", 200, r#"[{"Untagged": "shop-api:latest"}]"#),
+            ("DELETE /", 204, ""),
+        ]);
+        let runtime = Runtime::api("Docker", daemon.client());
+        let mut project = ComposeProject {
+            name: "shop".to_string(),
+            containers: vec![DockerContainer {
+                id: "c1".to_string(),
+                name: "shop-api-1".to_string(),
+                image: "shop-api".to_string(),
+                image_id: "sha256:a".to_string(),
+                running: false,
+                size: 0,
+                networks: Vec::new(),
+                project: Some("shop".to_string()),
+            }],
+            images: vec![test_image("sha256:a", &["shop-api:latest"])],
+            volumes: vec![DockerVolume { name: "shop_db".to_string(), size: 0, containers: 0, project: None }],
+            networks: vec![DockerNetwork { name: "shop_default".to_string(), project: None }],
+        };
+
+        let failures = runtime.tear_down(&project, &KeepList::default());
+
+        assert!(failures.is_empty());
+        let requests: Vec<String> = daemon
+            .requests()
+            .iter()
+            .map(|r| r.split(' ').take(2).collect::<Vec<_>>().join(" "))
+            .collect();
+        assert_eq!(
+            requests,
+            [
+                "DELETE /containers/c1",
+                "DELETE /networks/shop_default",
+                "DELETE /volumes/shop_db",
+                "DELETE /images/shop-api:latest",
+            ]
+        );
+
+        project.containers[0].running = true;
+        let failures = runtime.tear_down(&project, &KeepList::default());
+        assert_eq!(failures.len(), 1);
+        assert_eq!(daemon.requests().len(), 4);
+    }
+
+    #[test]
+    fn cli_runtimes_have_no_build_cache() {
+        let runtime = Runtime { name: "nerdctl", backend: Backend::Cli(Cli::Nerdctl) };
+        assert!(runtime.prune_build_cache(24).is_err());
+    }
+
+    #[test]
+    fn parses_nerdctl_sizes_and_timestamps() {
+        assert_eq!(parse_size("7.5 MiB"), Some(7_864_320));
+        assert_eq!(parse_size("1.2GB"), Some(1_200_000_000));
+        assert_eq!(parse_size("512B"), Some(512));
+        assert_eq!(parse_size(""), None);
+        assert_eq!(
+            parse_created_at("2024-03-01 12:00:00 +0000 UTC"),
+            Some(UNIX_EPOCH + Duration::from_secs(1_709_294_400))
+        );
+    }
+
+    #[test]
+    fn normalizes_image_references() {
+        assert_eq!(normalize_reference("docker.io/library/alpine:latest"), "alpine:latest");
+        assert_eq!(normalize_reference("alpine"), "alpine:latest");
+        assert_eq!(normalize_reference("localhost:5000/app"), "localhost:5000/app:latest");
+        assert_eq!(normalize_reference("ghcr.io/acme/app:1.2"), "ghcr.io/acme/app:1.2");
+    }
+
+    #[test]
+    fn reads_podman_json() {
+        let images: Vec<PodmanImage> = parse_json(
+            r#"[{"Id": "abc", "Names": ["quay.io/acme/app:1"], "Size": 2048, "SharedSize": 1024,
+                 "Created": 1700000000, "Containers": 1, "Dangling": false}]"#,
+        )
+        .unwrap();
+        assert_eq!(images[0].names.as_deref(), Some(&["quay.io/acme/app:1".to_string()][..]));
+
+        let networks: Vec<NamedNetwork> = parse_json(r#"[{"name": "podman", "driver": "bridge"}]"#).unwrap();
+        assert_eq!(networks[0].name, "podman");
+    }
+
+    #[test]
+    fn reads_nerdctl_container_networks() {
+        let attached = nerdctl_networks(
+            r#"[{"Id": "3f2a9c", "Config": {"Labels": {"nerdctl/networks": "[\"shop_default\",\"bridge\"]"}}},
+                {"Id": "7d1e04", "Config": {"Labels": {}},
+                 "NetworkSettings": {"Networks": {"unknown-eth0": {}, "web": {}}}}]"#,
+        )
+        .unwrap();
+        assert_eq!(attached["3f2a9c"], ["shop_default", "bridge"]);
+        assert_eq!(attached["7d1e04"], ["web"]);
+    }
+}
@@ -0,0 +1,379 @@
+use crate::{
+    docker::{compose::ComposeProject, retention, KeepList},
+    detectors::{Finding, Removal},
+    trash::Deletion,
+    App, CleanupRow, TopProcess,
+};
+
+/// What a cleanup action would remove, worked out from the last scan without touching anything.
+pub struct CleanupPlan {
+    pub action: String,
+    /// Paths, images or other resources, with their size in bytes when known.
+    pub items: Vec<(String, u64)>,
+    /// Bytes the action would free. Shared image layers are only counted once they lose
+    /// every tag, so this is not always the sum of the item sizes.
+    pub bytes: u64,
+    /// How paths are removed, for plans that only delete files rather than run a tool.
+    pub deletion: Option<Deletion>,
+    /// The part of `bytes` moved to the trash rather than removed for good.
+    pub trashed: u64,
+}
+
+impl CleanupPlan {
+    fn new(action: impl Into<String>) -> Self {
+        Self { action: action.into(), items: Vec::new(), bytes: 0, deletion: None, trashed: 0 }
+    }
+
+    fn push(&mut self, item: impl Into<String>, size: u64) {
+        self.items.push((item.into(), size));
+        self.bytes += size;
+    }
+
+    /// Adds a path deleted in the plan's deletion mode.
+    fn push_path(&mut self, item: impl Into<String>, size: u64) {
+        self.push(item, size);
+        if self.deletion == Some(Deletion::Trash) {
+            self.trashed += size;
+        }
+    }
+
+    /// Bytes removed for good: by tools, or by deleting paths without the trash.
+    pub fn permanent_bytes(&self) -> u64 {
+        self.bytes - self.trashed
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+/// The plan behind a cleanup menu row, or `None` for rows that only open another menu.
+pub fn plan(app: &App, row: CleanupRow) -> Option<CleanupPlan> {
+    let issues = app.issues.lock().unwrap();
+
+    let plan = match row {
+        CleanupRow::NodeModules => {
+            let mut plan = CleanupPlan::new("Clean node_modules");
+            plan.deletion = Some(app.deletion);
+            for nm in &issues.node_modules {
+                plan.push_path(nm.path.display().to_string(), nm.size);
+            }
+            plan
+        },
+        CleanupRow::DependencyDirs => {
+            findings("Clean CocoaPods/Carthage/SwiftPM dirs", &issues.dependency_dirs, app.deletion)
+        },
+        CleanupRow::DockerImages(_) | CleanupRow::KillProcess => return None,
+        CleanupRow::DockerRetention(index) => {
+            let entry = issues.runtimes.get(index)?;
+            let retention = retention::plan(&entry.usage, &app.retention, &app.docker_keep);
+            let mut plan = CleanupPlan::new(format!("Apply {} tag retention", entry.runtime.name));
+            for name in retention.tags.iter().chain(&retention.dangling) {
+                plan.items.push((name.clone(), 0));
+            }
+            plan.bytes = retention.bytes;
+            plan
+        },
+        CleanupRow::DockerContainers(index) => {
+            let entry = issues.runtimes.get(index)?;
+            let mut plan = CleanupPlan::new(format!("Prune stopped {} containers", entry.runtime.name));
+            for container in entry.usage.stopped_containers() {
+                plan.push(format!("container {}", container.name), container.size);
+            }
+            plan
+        },
+        CleanupRow::DockerVolumes(index) => {
+            let entry = issues.runtimes.get(index)?;
+            let mut plan = CleanupPlan::new(format!("Prune unused {} volumes", entry.runtime.name));
+            for volume in entry.usage.unused_volumes() {
+                plan.push(format!("volume {}", volume.name), volume.size);
+            }
+            plan
+        },
+        CleanupRow::DockerBuildCache(index) => {
+            let entry = issues.runtimes.get(index)?;
+            let mut plan = CleanupPlan::new(format!(
+                "Prune {} build cache older than {}h",
+                entry.runtime.name, app.build_cache_hours
+            ));
+            for record in entry.usage.build_cache_older_than(app.build_cache_hours) {
+                plan.push(record.description.clone(), record.size);
+            }
+            plan
+        },
+        CleanupRow::DockerNetworks(index) => {
+            let entry = issues.runtimes.get(index)?;
+            let mut plan = CleanupPlan::new(format!("Prune unused {} networks", entry.runtime.name));
+            for name in entry.usage.unused_networks() {
+                plan.push(format!("network {name}"), 0);
+            }
+            plan
+        },
+        CleanupRow::ComposeProjects(index) => {
+            let entry = issues.runtimes.get(index)?;
+            let mut plan = CleanupPlan::new(format!("Tear down stopped {} Compose projects", entry.runtime.name));
+            for project in crate::compose::projects(&entry.usage).iter().filter(|p| !p.is_running()) {
+                tear_down(&mut plan, project, &app.docker_keep);
+            }
+            plan
+        },
+        CleanupRow::Homebrew => {
+            let mut plan = CleanupPlan::new("Clean Homebrew");
+            if let Some(brew) = &issues.homebrew {
+                plan.items = brew
+                    .removable
+                    .iter()
+                    .map(|(path, size)| (path.display().to_string(), *size))
+                    .collect();
+                plan.bytes = brew.reclaimable;
+            }
+            plan
+        },
+        CleanupRow::Nix => {
+            let mut plan = CleanupPlan::new(format!(
+                "Collect Nix garbage older than {}d",
+                app.nix_keep_days
+            ));
+            if let Some(nix) = &issues.nix {
+                for generation in nix.generations_older_than(app.nix_keep_days) {
+                    plan.push(format!("{} generation {}", generation.profile, generation.number), 0);
+                }
+                if nix.dead_paths > 0 {
+                    plan.push(format!("{} unreachable store paths", nix.dead_paths), nix.dead_bytes);
+                }
+            }
+            plan
+        },
+        CleanupRow::AppCaches => {
+            let mut plan = CleanupPlan::new("Clear caches of closed apps");
+            plan.deletion = Some(app.deletion);
+            for cache in issues.app_caches.iter().filter(|cache| !app.is_app_running(cache)) {
+                for finding in &cache.caches {
+                    plan.push_path(finding.path.display().to_string(), finding.size);
+                }
+            }
+            plan
+        },
+        CleanupRow::SystemCache(index) => {
+            let cache = issues.system_caches.get(index)?;
+            let mut plan = CleanupPlan::new(format!("Run {} as root", cache.command.join(" ")));
+            plan.push(cache.path.display().to_string(), cache.size);
+            plan
+        },
+        CleanupRow::Category(index) => {
+            let category = issues.categories.get(index)?;
+            findings(&format!("Remove {}", category.name), &category.findings, app.deletion)
+        },
+    };
+
+    Some(plan)
+}
+
+/// Removing the items ticked in a cleanup checklist.
+pub fn checklist(app: &App, row: CleanupRow) -> CleanupPlan {
+    let title = crate::checklist_title(&app.issues.lock().unwrap(), row);
+    findings(&format!("Remove selected {title}"), &crate::checked_findings(app, row), app.deletion)
+}
+
+/// Removing `findings`. The plan only takes the deletion mode when every finding is a path to
+/// delete; findings removed by a tool are gone for good whatever the mode.
+fn findings(action: &str, findings: &[Finding], deletion: Deletion) -> CleanupPlan {
+    let deletes_path = |finding: &Finding| matches!(finding.removal, Removal::DeletePath);
+    let mut plan = CleanupPlan::new(action);
+    if !findings.is_empty() && findings.iter().all(deletes_path) {
+        plan.deletion = Some(deletion);
+    }
+    for finding in findings {
+        plan.push(finding.path.display().to_string(), finding.size);
+        for companion in &finding.companions {
+            plan.push(companion.display().to_string(), 0);
+        }
+        if deletion == Deletion::Trash && deletes_path(finding) {
+            plan.trashed += finding.size;
+        }
+    }
+    plan
+}
+
+/// Killing one of the heaviest processes; the plan's bytes are the memory it holds.
+pub fn kill(process: &TopProcess) -> CleanupPlan {
+    let mut plan = CleanupPlan::new(format!("Kill {}", process.name));
+    plan.push(
+        format!("{} (pid {}, {:.1}% CPU)", process.name, process.pid, process.cpu),
+        process.memory,
+    );
+    plan
+}
+
+/// Removing the images picked in the images checklist.
+pub fn images(app: &App, runtime: usize) -> CleanupPlan {
+    let issues = app.issues.lock().unwrap();
+    let Some(entry) = issues.runtimes.get(runtime) else {
+        return CleanupPlan::new("Remove images");
+    };
+
+    let mut plan = CleanupPlan::new(format!("Remove selected {} images", entry.runtime.name));
+    for image in entry.usage.images.iter().filter(|image| app.docker_selected.contains(&image.id)) {
+        plan.push(image.name(), image.unique_size());
+    }
+    plan
+}
+
+/// Tearing down one Compose project, in the order [`Runtime::tear_down`] removes things.
+///
+/// [`Runtime::tear_down`]: crate::docker::runtime::Runtime::tear_down
+pub fn compose_project(project: &ComposeProject, keep: &KeepList) -> CleanupPlan {
+    let mut plan = CleanupPlan::new(format!("Tear down Compose project {}", project.name));
+    tear_down(&mut plan, project, keep);
+    plan
+}
+
+fn tear_down(plan: &mut CleanupPlan, project: &ComposeProject, keep: &KeepList) {
+    for container in &project.containers {
+        plan.push(format!("{}: container {}", project.name, container.name), container.size);
+    }
+    for network in &project.networks {
+        plan.push(format!("{}: network {}", project.name, network.name), 0);
+    }
+    for volume in &project.volumes {
+        plan.push(format!("{}: volume {}", project.name, volume.name), volume.size);
+    }
+    for image in project.images.iter().filter(|image| !keep.keeps(image)) {
+        plan.push(format!("{}: image {}", project.name, image.name()), image.unique_size());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        detectors::{homebrew::HomebrewReport, system_caches::SystemCache, Category},
+        docker::{runtime::{Runtime, RuntimeUsage}, DockerClient, DockerUsage, DockerVolume},
+        IssuesData, NodeModulesEntry,
+    };
+    use std::path::{Path, PathBuf};
+
+    fn finding(path: &str, size: u64) -> Finding {
+        let mut finding = Finding::from_path(path, Path::new("/nonexistent"));
+        finding.path = PathBuf::from(path);
+        finding.size = size;
+        finding
+    }
+
+    fn app() -> App {
+        let docker = DockerUsage {
+            images: Vec::new(),
+            layers_size: 0,
+            containers: Vec::new(),
+            volumes: vec![
+                DockerVolume { name: "pgdata".to_string(), size: 300, containers: 0, project: None },
+                DockerVolume { name: "cache".to_string(), size: 50, containers: 1, project: None },
+            ],
+            build_cache: Vec::new(),
+            networks: Vec::new(),
+        };
+        App::with_issues(IssuesData {
+            node_modules: vec![
+                NodeModulesEntry { path: PathBuf::from("/p/a/node_modules"), size: 100 },
+                NodeModulesEntry { path: PathBuf::from("/p/b/node_modules"), size: 20 },
+            ],
+            runtimes: vec![RuntimeUsage {
+                runtime: Runtime::api("Docker", DockerClient::new("/nonexistent.sock")),
+                usage: docker,
+                error: None,
+            }],
+            categories: vec![Category::new(
+                "Snap and Flatpak",
+                vec![finding("/var/lib/snapd/snaps/core_1.snap", 70).with_command(vec!["snap".to_string()], true)],
+            )],
+            system_caches: vec![SystemCache {
+                name: "APT cache",
+                path: PathBuf::from("/var/cache/apt/archives"),
+                size: 40,
+                command: &["apt-get", "clean"],
+            }],
+            homebrew: Some(HomebrewReport {
+                removable: vec![(PathBuf::from("/brew/wget--1.21"), 5), (PathBuf::from("/brew/git--2.40"), 7)],
+                reclaimable: 12,
+                old_kegs: Vec::new(),
+                cache_size: 30,
+            }),
+            ..IssuesData::default()
+        })
+    }
+
+    fn items(plan: &CleanupPlan) -> Vec<(&str, u64)> {
+        plan.items.iter().map(|(item, size)| (item.as_str(), *size)).collect()
+    }
+
+    #[test]
+    fn plans_paths_with_the_chosen_deletion() {
+        let mut app = app();
+        let plan = plan(&app, CleanupRow::NodeModules).unwrap();
+        assert_eq!(items(&plan), [("/p/a/node_modules", 100), ("/p/b/node_modules", 20)]);
+        assert_eq!(plan.bytes, 120);
+        assert_eq!(plan.trashed, 120);
+        assert!(plan.deletion == Some(Deletion::Trash));
+
+        app.deletion = Deletion::Permanent;
+        let plan = super::plan(&app, CleanupRow::NodeModules).unwrap();
+        assert!(plan.deletion == Some(Deletion::Permanent));
+        assert_eq!(plan.permanent_bytes(), 120);
+    }
+
+    #[test]
+    fn splits_mixed_categories_into_trashed_and_permanent_bytes() {
+        let mut app = app();
+        app.issues.lock().unwrap().categories.push(Category::new(
+            "Virtual machines",
+            vec![
+                finding("/vms/ubuntu", 200).with_command(vec!["VBoxManage".to_string()], false),
+                finding("/utm/debian.utm", 30),
+            ],
+        ));
+
+        let plan = plan(&app, CleanupRow::Category(1)).unwrap();
+        assert!(plan.deletion.is_none());
+        assert_eq!((plan.bytes, plan.trashed, plan.permanent_bytes()), (230, 30, 200));
+
+        app.deletion = Deletion::Permanent;
+        let plan = super::plan(&app, CleanupRow::Category(1)).unwrap();
+        assert_eq!((plan.trashed, plan.permanent_bytes()), (0, 230));
+    }
+
+    #[test]
+    fn plans_tool_cleanups_without_a_deletion_mode() {
+        let app = app();
+
+        let snaps = plan(&app, CleanupRow::Category(0)).unwrap();
+        assert_eq!(snaps.action, "Remove Snap and Flatpak");
+        assert_eq!(items(&snaps), [("/var/lib/snapd/snaps/core_1.snap", 70)]);
+        assert!(snaps.deletion.is_none());
+
+        let brew = plan(&app, CleanupRow::Homebrew).unwrap();
+        assert_eq!(items(&brew), [("/brew/wget--1.21", 5), ("/brew/git--2.40", 7)]);
+        assert_eq!(brew.bytes, 12);
+
+        let apt = plan(&app, CleanupRow::SystemCache(0)).unwrap();
+        assert_eq!(apt.action, "Run apt-get clean as root");
+        assert_eq!(items(&apt), [("/var/cache/apt/archives", 40)]);
+
+        assert!(plan(&app, CleanupRow::Nix).unwrap().is_empty());
+    }
+
+    #[test]
+    fn plans_docker_prunes_from_the_last_scan() {
+        let app = app();
+        let volumes = plan(&app, CleanupRow::DockerVolumes(0)).unwrap();
+        assert_eq!(volumes.action, "Prune unused Docker volumes");
+        assert_eq!(items(&volumes), [("volume pgdata", 300)]);
+        assert!(plan(&app, CleanupRow::DockerVolumes(1)).is_none());
+    }
+
+    #[test]
+    fn has_no_plan_for_rows_that_open_a_menu() {
+        let app = app();
+        assert!(plan(&app, CleanupRow::DockerImages(0)).is_none());
+        assert!(plan(&app, CleanupRow::KillProcess).is_none());
+    }
+}
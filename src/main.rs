@@ -1,3 +1,11 @@
+mod cli;
+mod detectors;
+mod docker;
+mod dry_run;
+mod results;
+mod size;
+mod trash;
+
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
     execute,
@@ -7,19 +15,39 @@ use ratatui::{
     backend::CrosstermBackend,
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Style},
-    widgets::{Block, Borders, Gauge, List, ListItem, Paragraph},
+    widgets::{Block, Borders, Gauge, List, ListItem, ListState, Paragraph},
     Frame, Terminal,
 };
 use sysinfo::{System, Disks, ProcessesToUpdate};
 use std::{
+    collections::{HashMap, HashSet},
     error::Error,
     io,
-    path::PathBuf,
+    path::{Path, PathBuf},
+    process::Stdio,
     sync::{Arc, Mutex},
     thread,
-    time::{Duration, Instant},
+    time::{Duration, Instant, SystemTime},
+};
+use detectors::{
+    android, calculate_dir_size, conda, ide, project_dirs, snap_flatpak, swift_deps, system_caches,
+    terraform, toolchains, vm, xcode,
+    app_caches::{self, AppCache},
+    homebrew::{self, HomebrewReport},
+    nix::{self, NixReport},
+    system_caches::SystemCache,
+    Category, Finding, Removal,
+};
+use dry_run::CleanupPlan;
+use size::{bytes_to_gb, format_size, GB};
+use results::ActionResult;
+use trash::Deletion;
+use docker::{
+    compose,
+    retention::{self, RetentionPolicy},
+    runtime::{self, Runtime, RuntimeUsage},
+    DockerImage, ImageOrder, KeepList,
 };
-use walkdir::WalkDir;
 
 #[derive(Clone)]
 struct NodeModulesEntry {
@@ -27,13 +55,6 @@ struct NodeModulesEntry {
     size: u64,
 }
 
-#[derive(Clone)]
-struct DockerImage {
-    name: String,
-    size: String,
-    created: String,
-}
-
 #[derive(Clone)]
 struct TopProcess {
     name: String,
@@ -45,8 +66,14 @@ struct TopProcess {
 #[derive(Clone)]
 struct IssuesData {
     node_modules: Vec<NodeModulesEntry>,
-    docker_images: Vec<DockerImage>,
+    dependency_dirs: Vec<Finding>,
+    runtimes: Vec<RuntimeUsage>,
     top_processes: Vec<TopProcess>,
+    categories: Vec<Category>,
+    system_caches: Vec<SystemCache>,
+    homebrew: Option<HomebrewReport>,
+    nix: Option<NixReport>,
+    app_caches: Vec<AppCache>,
     scanning: bool,
 }
 
@@ -54,8 +81,14 @@ impl Default for IssuesData {
     fn default() -> Self {
         Self {
             node_modules: Vec::new(),
-            docker_images: Vec::new(),
+            dependency_dirs: Vec::new(),
+            runtimes: Vec::new(),
             top_processes: Vec::new(),
+            categories: Vec::new(),
+            system_caches: Vec::new(),
+            homebrew: None,
+            nix: None,
+            app_caches: Vec::new(),
             scanning: true,
         }
     }
@@ -65,6 +98,55 @@ enum AppMode {
     Normal,
     CleanupMenu { selected: usize },
     KillProcessMenu { selected: usize },
+    Checklist { row: CleanupRow, selected: usize },
+    DockerImagesMenu { runtime: usize, selected: usize },
+    RetentionPreview { runtime: usize },
+    ComposeMenu { runtime: usize, selected: usize },
+    DryRun { plan: CleanupPlan, back: Box<AppMode>, offset: usize },
+    /// One entry of the session history, opened after each action or with `r`.
+    Results { index: usize, offset: usize, back: Box<AppMode> },
+    Confirm {
+        action: PendingAction,
+        plan: CleanupPlan,
+        /// What has been typed so far, for actions that need `yes` spelled out.
+        typed: Option<String>,
+        back: Box<AppMode>,
+        offset: usize,
+    },
+}
+
+/// An action waiting in the confirmation dialog. Actions whose targets can shift under a
+/// rescan carry what was confirmed, so they never act on something the dialog did not show.
+#[derive(Clone)]
+enum PendingAction {
+    Cleanup(CleanupRow),
+    Checklist(CleanupRow),
+    RemoveImages(usize),
+    Retention { runtime: usize, plan: retention::RetentionPlan },
+    TearDown { runtime: usize, project: String },
+    Kill { pid: u32 },
+}
+
+/// Permanent deletions freeing more than this need `yes` typed out rather than a single key.
+const TYPE_YES_ABOVE: u64 = GB;
+
+#[derive(Clone, Copy, PartialEq)]
+enum CleanupRow {
+    NodeModules,
+    DependencyDirs,
+    DockerImages(usize),
+    DockerRetention(usize),
+    DockerContainers(usize),
+    DockerVolumes(usize),
+    DockerBuildCache(usize),
+    DockerNetworks(usize),
+    ComposeProjects(usize),
+    Homebrew,
+    Nix,
+    AppCaches,
+    KillProcess,
+    SystemCache(usize),
+    Category(usize),
 }
 
 struct App {
@@ -73,6 +155,23 @@ struct App {
     last_update: Instant,
     issues: Arc<Mutex<IssuesData>>,
     mode: AppMode,
+    /// Results of every action run this session, oldest first.
+    history: Vec<ActionResult>,
+    /// Every action shows what it would remove instead of running.
+    dry_run: bool,
+    /// Whether removed paths go to the trash or are deleted for good.
+    deletion: Deletion,
+    nix_keep_days: u64,
+    image_order: ImageOrder,
+    build_cache_hours: u64,
+    docker_keep: KeepList,
+    docker_selected: HashSet<String>,
+    docker_failures: HashMap<String, String>,
+    retention: RetentionPolicy,
+    /// Paths ticked in the open cleanup checklist.
+    checked: HashSet<PathBuf>,
+    /// Lowercased names of the running processes, as of the last refresh.
+    running: HashSet<String>,
 }
 
 impl App {
@@ -87,12 +186,50 @@ impl App {
             scan_issues(issues_clone);
         });
 
-        Self {
+        let mut app = Self {
             system,
             disks: Disks::new_with_refreshed_list(),
             last_update: Instant::now(),
             issues,
             mode: AppMode::Normal,
+            history: Vec::new(),
+            dry_run: false,
+            deletion: Deletion::default(),
+            nix_keep_days: 30,
+            image_order: ImageOrder::default(),
+            build_cache_hours: 24,
+            docker_keep: KeepList::load(Path::new(&home_dir())),
+            docker_selected: HashSet::new(),
+            docker_failures: HashMap::new(),
+            retention: RetentionPolicy::default(),
+            checked: HashSet::new(),
+            running: HashSet::new(),
+        };
+        app.refresh_running();
+        app
+    }
+
+    /// An app over already scanned `issues`, with no system stats and no background scan.
+    #[cfg(test)]
+    fn with_issues(issues: IssuesData) -> Self {
+        Self {
+            system: System::new(),
+            disks: Disks::new(),
+            last_update: Instant::now(),
+            issues: Arc::new(Mutex::new(IssuesData { scanning: false, ..issues })),
+            mode: AppMode::Normal,
+            history: Vec::new(),
+            dry_run: false,
+            deletion: Deletion::default(),
+            nix_keep_days: 30,
+            image_order: ImageOrder::default(),
+            build_cache_hours: 24,
+            docker_keep: KeepList::default(),
+            docker_selected: HashSet::new(),
+            docker_failures: HashMap::new(),
+            retention: RetentionPolicy::default(),
+            checked: HashSet::new(),
+            running: HashSet::new(),
         }
     }
 
@@ -101,6 +238,7 @@ impl App {
             self.system.refresh_all();
             self.disks.refresh(true);
             self.update_top_processes();
+            self.refresh_running();
             self.last_update = Instant::now();
         }
     }
@@ -129,6 +267,17 @@ impl App {
         }
     }
 
+    fn refresh_running(&mut self) {
+        self.running = self.system.processes()
+            .values()
+            .map(|p| p.name().to_string_lossy().to_lowercase())
+            .collect();
+    }
+
+    fn is_app_running(&self, cache: &AppCache) -> bool {
+        cache.is_running(&self.running)
+    }
+
     fn disk_usage(&self) -> (u64, u64, f64) {
         let mut total = 0;
         let mut used = 0;
@@ -170,47 +319,74 @@ impl App {
     }
 }
 
+fn home_dir() -> String {
+    std::env::var("HOME").unwrap_or_else(|_| "/Users".to_string())
+}
+
 fn scan_issues(issues: Arc<Mutex<IssuesData>>) {
-    let home_dir = std::env::var("HOME").unwrap_or_else(|_| "/Users".to_string());
+    let home_dir = home_dir();
+    let home = PathBuf::from(&home_dir);
     
-    let mut node_modules = scan_node_modules(&home_dir);
-    node_modules.sort_by(|a, b| b.size.cmp(&a.size));
+    let projects = project_dirs(&home);
+
+    let mut node_modules = scan_node_modules(&projects);
+    node_modules.sort_by_key(|nm| std::cmp::Reverse(nm.size));
     node_modules.truncate(10);
 
-    let docker_images = scan_docker_images();
+    let mut dependency_dirs = swift_deps::scan(&home, &projects);
+    dependency_dirs.sort_by_key(|d| std::cmp::Reverse(d.size));
+
+    let runtimes = runtime::scan(&home);
+
+    let terraform = terraform::scan(&home, &projects);
+    let mut terraform_category = Category::new("Terraform", terraform.findings);
+    if terraform.shared_cache_savings > 0 {
+        terraform_category.note = Some(format!(
+            "A shared plugin cache would save {}",
+            format_size(terraform.shared_cache_savings)
+        ));
+    }
+
+    let categories = vec![
+        Category::new("Xcode", xcode::scan(&home)),
+        Category::new("Android", android::scan(&home)),
+        terraform_category,
+        Category::new("IDE caches", ide::scan(Path::new("/"), &home)),
+        Category::new("Snap & Flatpak", snap_flatpak::scan(Path::new("/"), &home)),
+        Category::new("Conda", conda::scan(&home)),
+        Category::new("Virtual machines", vm::scan(Path::new("/"), &home)),
+        Category::new("Unused toolchains", toolchains::scan(&home, &projects)),
+    ];
+
+    let system_caches = system_caches::scan(Path::new("/"));
+    let homebrew = homebrew::scan();
+    let nix = nix::scan(&home);
+    let app_caches = app_caches::scan(&home);
 
     if let Ok(mut data) = issues.lock() {
         data.node_modules = node_modules;
-        data.docker_images = docker_images;
+        data.dependency_dirs = dependency_dirs;
+        data.runtimes = runtimes;
+        data.categories = categories.into_iter().filter(|c| !c.findings.is_empty()).collect();
+        data.system_caches = system_caches;
+        data.homebrew = homebrew;
+        data.nix = nix;
+        data.app_caches = app_caches;
         data.scanning = false;
     }
 }
 
-fn scan_node_modules(base_path: &str) -> Vec<NodeModulesEntry> {
+fn scan_node_modules(projects: &[PathBuf]) -> Vec<NodeModulesEntry> {
     let mut results = Vec::new();
-    let max_depth = 6;
 
-    for entry in WalkDir::new(base_path)
-        .max_depth(max_depth)
-        .follow_links(false)
-        .into_iter()
-        .filter_entry(|e| {
-            let name = e.file_name().to_string_lossy();
-            !name.starts_with('.') && 
-            name != "Library" && 
-            name != "System" &&
-            name != "Applications"
-        })
-        .filter_map(|e| e.ok())
-    {
-        if entry.file_type().is_dir() && entry.file_name() == "node_modules" {
-            if let Ok(size) = calculate_dir_size(entry.path()) {
-                if size > 100_000_000 {
-                    results.push(NodeModulesEntry {
-                        path: entry.path().to_path_buf(),
-                        size,
-                    });
-                }
+    for dir in projects {
+        if dir.file_name().is_some_and(|name| name == "node_modules") {
+            let size = calculate_dir_size(dir);
+            if size > 100_000_000 {
+                results.push(NodeModulesEntry {
+                    path: dir.clone(),
+                    size,
+                });
             }
         }
     }
@@ -218,56 +394,26 @@ fn scan_node_modules(base_path: &str) -> Vec<NodeModulesEntry> {
     results
 }
 
-fn calculate_dir_size(path: &std::path::Path) -> Result<u64, io::Error> {
-    let mut total = 0;
-    
-    for entry in WalkDir::new(path)
-        .follow_links(false)
-        .into_iter()
-        .filter_map(|e| e.ok())
-    {
-        if entry.file_type().is_file() {
-            if let Ok(metadata) = entry.metadata() {
-                total += metadata.len();
-            }
+fn rescan_runtimes(app: &App) {
+    let shared = Arc::clone(&app.issues);
+    thread::spawn(move || {
+        let runtimes = runtime::scan(Path::new(&home_dir()));
+        if let Ok(mut data) = shared.lock() {
+            data.runtimes = runtimes;
         }
-    }
+    });
+}
 
-    Ok(total)
-}
-
-fn scan_docker_images() -> Vec<DockerImage> {
-    let output = std::process::Command::new("docker")
-        .args(["images", "--format", "{{.Repository}}:{{.Tag}}\t{{.Size}}\t{{.CreatedAt}}"])
-        .output();
-
-    if let Ok(output) = output {
-        if output.status.success() {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            return stdout
-                .lines()
-                .filter(|line| !line.is_empty())
-                .filter_map(|line| {
-                    let parts: Vec<&str> = line.split('\t').collect();
-                    if parts.len() >= 3 {
-                        Some(DockerImage {
-                            name: parts[0].to_string(),
-                            size: parts[1].to_string(),
-                            created: parts[2].to_string(),
-                        })
-                    } else {
-                        None
-                    }
-                })
-                .take(10)
-                .collect();
+fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        if let Err(err) = cli::run(&args) {
+            eprintln!("macmon: {err}");
+            std::process::exit(1);
         }
+        return Ok(());
     }
 
-    Vec::new()
-}
-
-fn main() -> Result<(), Box<dyn Error>> {
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
@@ -300,246 +446,1639 @@ fn run_app(
         app.update();
         terminal.draw(|f| ui(f, app))?;
 
-        if event::poll(Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
-                match &app.mode {
-                    AppMode::Normal => match key.code {
-                        KeyCode::Char('q') => return Ok(()),
-                        KeyCode::Char('c') => {
-                            app.mode = AppMode::CleanupMenu { selected: 0 };
+        if event::poll(Duration::from_millis(100))? && let Event::Key(key) = event::read()? {
+            // `n` previews the action under the cursor; with dry-run on, so does Enter.
+            let preview = key.code == KeyCode::Char('n') || key.code == KeyCode::Enter && app.dry_run;
+            match &app.mode {
+                AppMode::Normal => match key.code {
+                    KeyCode::Char('q') => return Ok(()),
+                    KeyCode::Char('c') => {
+                        app.mode = AppMode::CleanupMenu { selected: 0 };
+                    },
+                    KeyCode::Char('d') => {
+                        app.dry_run = !app.dry_run;
+                    },
+                    KeyCode::Char('r') if !app.history.is_empty() => {
+                        app.mode = AppMode::Results {
+                            index: app.history.len() - 1,
+                            offset: 0,
+                            back: Box::new(AppMode::Normal),
+                        };
+                    },
+                    KeyCode::Char('s') => {
+                        app.image_order = app.image_order.toggle();
+                    },
+                    _ => {}
+                },
+                AppMode::CleanupMenu { selected } => match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => {
+                        app.mode = AppMode::Normal;
+                    },
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        let max = cleanup_menu_len(app) - 1;
+                        let new_selected = if *selected > 0 { selected - 1 } else { max };
+                        app.mode = AppMode::CleanupMenu { selected: new_selected };
+                    },
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        let max = cleanup_menu_len(app) - 1;
+                        let new_selected = if *selected < max { selected + 1 } else { 0 };
+                        app.mode = AppMode::CleanupMenu { selected: new_selected };
+                    },
+                    KeyCode::Left | KeyCode::Right if cleanup_row(app, *selected) == Some(CleanupRow::Nix) => {
+                        app.nix_keep_days = if key.code == KeyCode::Left {
+                            app.nix_keep_days.saturating_sub(7).max(1)
+                        } else {
+                            app.nix_keep_days + 7
+                        };
+                    },
+                    KeyCode::Left | KeyCode::Right if matches!(cleanup_row(app, *selected), Some(CleanupRow::DockerRetention(_))) => {
+                        app.retention.keep_newest = if key.code == KeyCode::Left {
+                            app.retention.keep_newest.saturating_sub(1)
+                        } else {
+                            app.retention.keep_newest + 1
+                        };
+                    },
+                    KeyCode::Left | KeyCode::Right if matches!(cleanup_row(app, *selected), Some(CleanupRow::DockerBuildCache(_))) => {
+                        app.build_cache_hours = if key.code == KeyCode::Left {
+                            app.build_cache_hours.saturating_sub(24)
+                        } else {
+                            app.build_cache_hours + 24
+                        };
+                    },
+                    KeyCode::Char('d') => {
+                        app.dry_run = !app.dry_run;
+                    },
+                    KeyCode::Char('x') => {
+                        app.deletion = app.deletion.toggle();
+                    },
+                    KeyCode::Char('n') => {
+                        if let Some(plan) = cleanup_row(app, *selected).and_then(|row| dry_run::plan(app, row)) {
+                            show_plan(app, plan);
+                        }
+                    },
+                    KeyCode::Enter => match cleanup_row(app, *selected) {
+                        Some(CleanupRow::KillProcess) => {
+                            app.mode = AppMode::KillProcessMenu { selected: 0 };
                         },
-                        _ => {}
+                        Some(row) if has_checklist(row) => {
+                            open_checklist(app, row);
+                        },
+                        Some(CleanupRow::DockerImages(runtime)) => {
+                            app.docker_selected.clear();
+                            app.docker_failures.clear();
+                            app.mode = AppMode::DockerImagesMenu { runtime, selected: 0 };
+                        },
+                        Some(CleanupRow::DockerRetention(runtime)) => {
+                            app.mode = AppMode::RetentionPreview { runtime };
+                        },
+                        Some(CleanupRow::ComposeProjects(runtime)) => {
+                            app.mode = AppMode::ComposeMenu { runtime, selected: 0 };
+                        },
+                        Some(row) if app.dry_run => {
+                            if let Some(plan) = dry_run::plan(app, row) {
+                                show_plan(app, plan);
+                            }
+                        },
+                        Some(row) => {
+                            if let Some(plan) = dry_run::plan(app, row) {
+                                confirm(app, PendingAction::Cleanup(row), plan);
+                            }
+                        },
+                        None => {}
+                    },
+                    _ => {}
+                },
+                AppMode::KillProcessMenu { selected } => match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => {
+                        app.mode = AppMode::CleanupMenu { selected: 0 };
+                    },
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        let issues = app.issues.lock().unwrap();
+                        let max = issues.top_processes.len().saturating_sub(1);
+                        let new_selected = if *selected > 0 { selected - 1 } else { max };
+                        app.mode = AppMode::KillProcessMenu { selected: new_selected };
                     },
-                    AppMode::CleanupMenu { selected } => match key.code {
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        let issues = app.issues.lock().unwrap();
+                        let max = issues.top_processes.len().saturating_sub(1);
+                        let new_selected = if *selected < max { selected + 1 } else { 0 };
+                        app.mode = AppMode::KillProcessMenu { selected: new_selected };
+                    },
+                    _ if preview => {
+                        let process = app.issues.lock().unwrap().top_processes.get(*selected).cloned();
+                        if let Some(process) = process {
+                            show_plan(app, dry_run::kill(&process));
+                        }
+                    },
+                    KeyCode::Enter => {
+                        let process = app.issues.lock().unwrap().top_processes.get(*selected).cloned();
+                        if let Some(process) = process {
+                            confirm(app, PendingAction::Kill { pid: process.pid }, dry_run::kill(&process));
+                        }
+                    },
+                    _ => {}
+                },
+                AppMode::DockerImagesMenu { runtime, selected } => {
+                    let (runtime, selected) = (*runtime, *selected);
+                    let max = docker_images(app, runtime).len().saturating_sub(1);
+                    match key.code {
                         KeyCode::Char('q') | KeyCode::Esc => {
-                            app.mode = AppMode::Normal;
+                            let rows = cleanup_rows(&app.issues.lock().unwrap());
+                            let selected = rows
+                                .iter()
+                                .position(|row| *row == CleanupRow::DockerImages(runtime))
+                                .unwrap_or(0);
+                            app.mode = AppMode::CleanupMenu { selected };
                         },
                         KeyCode::Up | KeyCode::Char('k') => {
-                            let new_selected = if *selected > 0 { selected - 1 } else { 3 };
-                            app.mode = AppMode::CleanupMenu { selected: new_selected };
+                            let new_selected = if selected > 0 { selected - 1 } else { max };
+                            app.mode = AppMode::DockerImagesMenu { runtime, selected: new_selected };
                         },
                         KeyCode::Down | KeyCode::Char('j') => {
-                            let new_selected = if *selected < 3 { selected + 1 } else { 0 };
-                            app.mode = AppMode::CleanupMenu { selected: new_selected };
+                            let new_selected = if selected < max { selected + 1 } else { 0 };
+                            app.mode = AppMode::DockerImagesMenu { runtime, selected: new_selected };
                         },
-                        KeyCode::Enter => {
-                            if *selected == 3 {
-                                app.mode = AppMode::KillProcessMenu { selected: 0 };
-                            } else {
-                                execute_cleanup(app, *selected)?;
-                                app.mode = AppMode::Normal;
+                        KeyCode::Char(' ') => {
+                            if let Some(image) = docker_images(app, runtime).into_iter().nth(selected)
+                                && !is_protected(app, runtime, &image)
+                                && !app.docker_selected.remove(&image.id)
+                            {
+                                app.docker_selected.insert(image.id);
+                            }
+                        },
+                        KeyCode::Char('p') => {
+                            if let Some(image) = docker_images(app, runtime).into_iter().nth(selected) {
+                                if let Err(err) = app.docker_keep.toggle(&image) {
+                                    let mut result = ActionResult::new("Update Docker keep-list");
+                                    result.failed(image.name(), err);
+                                    show_result(app, result);
+                                }
+                                if app.docker_keep.keeps(&image) {
+                                    app.docker_selected.remove(&image.id);
+                                }
                             }
                         },
+                        _ if preview => {
+                            show_plan(app, dry_run::images(app, runtime));
+                        },
+                        KeyCode::Enter if !app.docker_selected.is_empty() => {
+                            confirm(app, PendingAction::RemoveImages(runtime), dry_run::images(app, runtime));
+                        },
                         _ => {}
+                    }
+                },
+                AppMode::RetentionPreview { runtime } => match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => {
+                        let rows = cleanup_rows(&app.issues.lock().unwrap());
+                        let selected = rows
+                            .iter()
+                            .position(|row| *row == CleanupRow::DockerRetention(*runtime))
+                            .unwrap_or(0);
+                        app.mode = AppMode::CleanupMenu { selected };
+                    },
+                    _ if preview => {
+                        if let Some(plan) = dry_run::plan(app, CleanupRow::DockerRetention(*runtime)) {
+                            show_plan(app, plan);
+                        }
+                    },
+                    KeyCode::Enter => {
+                        let runtime = *runtime;
+                        let retention = retention_plan(app, runtime);
+                        if let Some(plan) = dry_run::plan(app, CleanupRow::DockerRetention(runtime)) {
+                            confirm(app, PendingAction::Retention { runtime, plan: retention }, plan);
+                        }
                     },
-                    AppMode::KillProcessMenu { selected } => match key.code {
+                    _ => {}
+                },
+                AppMode::ComposeMenu { runtime, selected } => {
+                    let (runtime, selected) = (*runtime, *selected);
+                    let max = compose_projects(app, runtime).len().saturating_sub(1);
+                    match key.code {
                         KeyCode::Char('q') | KeyCode::Esc => {
-                            app.mode = AppMode::CleanupMenu { selected: 0 };
+                            let rows = cleanup_rows(&app.issues.lock().unwrap());
+                            let selected = rows
+                                .iter()
+                                .position(|row| *row == CleanupRow::ComposeProjects(runtime))
+                                .unwrap_or(0);
+                            app.mode = AppMode::CleanupMenu { selected };
                         },
                         KeyCode::Up | KeyCode::Char('k') => {
-                            let issues = app.issues.lock().unwrap();
-                            let max = issues.top_processes.len().saturating_sub(1);
-                            let new_selected = if *selected > 0 { selected - 1 } else { max };
-                            app.mode = AppMode::KillProcessMenu { selected: new_selected };
+                            let new_selected = if selected > 0 { selected - 1 } else { max };
+                            app.mode = AppMode::ComposeMenu { runtime, selected: new_selected };
                         },
                         KeyCode::Down | KeyCode::Char('j') => {
-                            let issues = app.issues.lock().unwrap();
-                            let max = issues.top_processes.len().saturating_sub(1);
-                            let new_selected = if *selected < max { selected + 1 } else { 0 };
-                            app.mode = AppMode::KillProcessMenu { selected: new_selected };
+                            let new_selected = if selected < max { selected + 1 } else { 0 };
+                            app.mode = AppMode::ComposeMenu { runtime, selected: new_selected };
+                        },
+                        _ if preview => {
+                            if let Some(project) = compose_projects(app, runtime).get(selected) {
+                                show_plan(app, dry_run::compose_project(project, &app.docker_keep));
+                            }
                         },
                         KeyCode::Enter => {
-                            kill_process(app, *selected)?;
-                            app.mode = AppMode::CleanupMenu { selected: 0 };
+                            if let Some(project) = compose_projects(app, runtime).get(selected)
+                                && !project.is_running()
+                            {
+                                let plan = dry_run::compose_project(project, &app.docker_keep);
+                                let project = project.name.clone();
+                                confirm(app, PendingAction::TearDown { runtime, project }, plan);
+                            }
                         },
                         _ => {}
-                    },
-                }
+                    }
+                },
+                AppMode::Checklist { row, selected } => {
+                    let (row, selected) = (*row, *selected);
+                    let max = checklist_items(app, row).len().saturating_sub(1);
+                    match key.code {
+                        KeyCode::Char('q') | KeyCode::Esc => {
+                            let rows = cleanup_rows(&app.issues.lock().unwrap());
+                            let selected = rows.iter().position(|r| *r == row).unwrap_or(0);
+                            app.mode = AppMode::CleanupMenu { selected };
+                        },
+                        KeyCode::Up | KeyCode::Char('k') => {
+                            let new_selected = if selected > 0 { selected - 1 } else { max };
+                            app.mode = AppMode::Checklist { row, selected: new_selected };
+                        },
+                        KeyCode::Down | KeyCode::Char('j') => {
+                            let new_selected = if selected < max { selected + 1 } else { 0 };
+                            app.mode = AppMode::Checklist { row, selected: new_selected };
+                        },
+                        KeyCode::Char(' ' | 'a' | 'u' | 'i') => update_checked(app, row, selected, key.code),
+                        _ if preview => {
+                            show_plan(app, dry_run::checklist(app, row));
+                        },
+                        KeyCode::Enter if !app.checked.is_empty() => {
+                            confirm(app, PendingAction::Checklist(row), dry_run::checklist(app, row));
+                        },
+                        _ => {}
+                    }
+                },
+                AppMode::Results { index, .. } => {
+                    let (index, last) = (*index, app.history.len().saturating_sub(1));
+                    match key.code {
+                        KeyCode::Char('q') | KeyCode::Esc | KeyCode::Enter => close_overlay(app),
+                        KeyCode::Up | KeyCode::Char('k') => scroll_overlay(app, false),
+                        KeyCode::Down | KeyCode::Char('j') => scroll_overlay(app, true),
+                        KeyCode::Left | KeyCode::Char('h') | KeyCode::Right | KeyCode::Char('l') => {
+                            let new_index = if matches!(key.code, KeyCode::Left | KeyCode::Char('h')) {
+                                index.saturating_sub(1)
+                            } else {
+                                (index + 1).min(last)
+                            };
+                            if let AppMode::Results { index, offset, .. } = &mut app.mode {
+                                *index = new_index;
+                                *offset = 0;
+                            }
+                        },
+                        _ => {}
+                    }
+                },
+                AppMode::DryRun { .. } => match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc | KeyCode::Enter => close_overlay(app),
+                    KeyCode::Up | KeyCode::Char('k') => scroll_overlay(app, false),
+                    KeyCode::Down | KeyCode::Char('j') => scroll_overlay(app, true),
+                    _ => {}
+                },
+                AppMode::Confirm { action, typed, .. } => {
+                    let action = action.clone();
+                    let typing = typed.is_some();
+                    let confirmed = match typed {
+                        Some(typed) => key.code == KeyCode::Enter && typed == "yes",
+                        None => key.code == KeyCode::Char('y'),
+                    };
+                    match key.code {
+                        _ if confirmed => {
+                            close_overlay(app);
+                            run_pending(terminal, app, action)?;
+                        },
+                        KeyCode::Esc => close_overlay(app),
+                        KeyCode::Char('q') | KeyCode::Char('n') if !typing => close_overlay(app),
+                        KeyCode::Up => scroll_overlay(app, false),
+                        KeyCode::Down => scroll_overlay(app, true),
+                        KeyCode::Char('k') if !typing => scroll_overlay(app, false),
+                        KeyCode::Char('j') if !typing => scroll_overlay(app, true),
+                        KeyCode::Char(c) => {
+                            if let AppMode::Confirm { typed: Some(typed), .. } = &mut app.mode {
+                                typed.push(c);
+                            }
+                        },
+                        KeyCode::Backspace => {
+                            if let AppMode::Confirm { typed: Some(typed), .. } = &mut app.mode {
+                                typed.pop();
+                            }
+                        },
+                        _ => {}
+                    }
+                },
             }
         }
     }
 }
 
-fn execute_cleanup(app: &App, option: usize) -> io::Result<()> {
-    let issues = app.issues.lock().unwrap();
-    
-    match option {
-        0 => {
-            for nm in &issues.node_modules {
-                let _ = std::fs::remove_dir_all(&nm.path);
+/// Asks before running `action`, showing `plan` as what it will affect. Plans removing more
+/// than [`TYPE_YES_ABOVE`] for good need `yes` typed out; everything else takes a single `y`.
+fn confirm(app: &mut App, action: PendingAction, plan: CleanupPlan) {
+    let kill = matches!(action, PendingAction::Kill { .. });
+    let typed = (!kill && plan.permanent_bytes() > TYPE_YES_ABOVE).then(String::new);
+    let back = std::mem::replace(&mut app.mode, AppMode::Normal);
+    app.mode = AppMode::Confirm { action, plan, typed, back: Box::new(back), offset: 0 };
+}
+
+fn run_pending(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    app: &mut App,
+    action: PendingAction,
+) -> io::Result<()> {
+    let result = match action {
+        PendingAction::Cleanup(CleanupRow::SystemCache(index)) => {
+            app.mode = AppMode::Normal;
+            clean_system_cache(terminal, app, index)?
+        },
+        PendingAction::Cleanup(row) => {
+            app.mode = AppMode::Normal;
+            execute_cleanup(app, row)
+        },
+        PendingAction::Checklist(row) => {
+            let result = remove_checked(terminal, app, row)?;
+            let len = checklist_items(app, row).len();
+            if let AppMode::Checklist { selected, .. } = &mut app.mode {
+                *selected = (*selected).min(len.saturating_sub(1));
             }
+            result
         },
-        1 => {
-            let _ = std::process::Command::new("docker")
-                .args(["image", "prune", "-af"])
-                .output();
+        PendingAction::RemoveImages(runtime) => {
+            let result = remove_selected_images(app, runtime);
+            let len = docker_images(app, runtime).len();
+            if let AppMode::DockerImagesMenu { selected, .. } = &mut app.mode {
+                *selected = (*selected).min(len.saturating_sub(1));
+            }
+            result
         },
-        2 => {
-            let _ = std::process::Command::new("brew")
-                .args(["cleanup", "-s"])
-                .output();
+        PendingAction::Retention { runtime, plan } => {
+            app.mode = AppMode::Normal;
+            apply_retention(app, runtime, &plan)
         },
-        _ => {}
-    }
-    
+        PendingAction::TearDown { runtime, project } => tear_down_project(app, runtime, &project),
+        PendingAction::Kill { pid } => {
+            app.mode = AppMode::CleanupMenu { selected: 0 };
+            kill_process(app, pid)
+        },
+    };
+    show_result(app, result);
     Ok(())
 }
 
-fn kill_process(app: &App, index: usize) -> io::Result<()> {
-    let issues = app.issues.lock().unwrap();
-    
-    if let Some(process) = issues.top_processes.get(index) {
-        let _ = std::process::Command::new("kill")
-            .arg("-9")
-            .arg(process.pid.to_string())
-            .output();
+/// Keeps `result` in the session history and shows it over the current screen.
+fn show_result(app: &mut App, result: ActionResult) {
+    app.history.push(result);
+    let back = std::mem::replace(&mut app.mode, AppMode::Normal);
+    app.mode = AppMode::Results { index: app.history.len() - 1, offset: 0, back: Box::new(back) };
+}
+
+/// Closes a dry-run, confirmation or results overlay, returning to the screen it was opened from.
+fn close_overlay(app: &mut App) {
+    app.mode = match std::mem::replace(&mut app.mode, AppMode::Normal) {
+        AppMode::DryRun { back, .. } | AppMode::Confirm { back, .. } | AppMode::Results { back, .. } => *back,
+        mode => mode,
+    };
+}
+
+fn scroll_overlay(app: &mut App, down: bool) {
+    let len = match &app.mode {
+        AppMode::DryRun { plan, .. } | AppMode::Confirm { plan, .. } => plan.items.len(),
+        AppMode::Results { index, .. } => app.history.get(*index).map_or(0, |r| result_lines(r).len()),
+        _ => return,
+    };
+    if let AppMode::DryRun { offset, .. } | AppMode::Confirm { offset, .. } | AppMode::Results { offset, .. } =
+        &mut app.mode
+    {
+        *offset = if down { (*offset + 1).min(len.saturating_sub(1)) } else { offset.saturating_sub(1) };
     }
-    
-    Ok(())
 }
 
-fn ui(f: &mut Frame, app: &App) {
-    match &app.mode {
-        AppMode::Normal => {
-            let chunks = Layout::default()
-                .direction(Direction::Vertical)
-                .constraints([
-                    Constraint::Length(10),
-                    Constraint::Min(5),
-                    Constraint::Length(3),
-                ])
-                .split(f.area());
+/// Shows what an action would remove, returning to the current menu afterwards.
+fn show_plan(app: &mut App, plan: CleanupPlan) {
+    let back = std::mem::replace(&mut app.mode, AppMode::Normal);
+    app.mode = AppMode::DryRun { plan, back: Box::new(back), offset: 0 };
+}
 
-            render_metrics(f, app, chunks[0]);
-            render_issues(f, app, chunks[1]);
-            render_help(f, chunks[2]);
+fn execute_cleanup(app: &mut App, row: CleanupRow) -> ActionResult {
+    let issues = app.issues.lock().unwrap();
+    
+    match row {
+        CleanupRow::DockerContainers(index) |
+        CleanupRow::DockerVolumes(index) |
+        CleanupRow::DockerBuildCache(index) |
+        CleanupRow::DockerNetworks(index) => {
+            let Some(runtime) = issues.runtimes.get(index).map(|r| r.runtime.clone()) else {
+                return ActionResult::new("Prune");
+            };
+            let result = prune_docker(&runtime, row, app.build_cache_hours);
+            rescan_runtimes(app);
+            result
         },
-        AppMode::CleanupMenu { selected } => {
-            render_cleanup_menu(f, app, *selected);
+        CleanupRow::Homebrew => {
+            let mut result = ActionResult::new("Clean Homebrew");
+            if issues.homebrew.is_none() {
+                result.failed("brew", "Homebrew is not installed");
+                return result;
+            }
+
+            match homebrew::cleanup() {
+                Ok(output) => {
+                    result.succeeded("brew cleanup -s", output.freed);
+                    result.add_stderr(&output.stderr);
+                },
+                Err(err) => result.failed("brew cleanup -s", err),
+            }
+
+            let shared = Arc::clone(&app.issues);
+            thread::spawn(move || {
+                let report = homebrew::scan();
+                if let Ok(mut data) = shared.lock() {
+                    data.homebrew = report;
+                }
+            });
+            result
         },
-        AppMode::KillProcessMenu { selected } => {
-            render_kill_process_menu(f, app, *selected);
-        }
+        CleanupRow::Nix => {
+            let mut result = ActionResult::new("Collect Nix garbage");
+            let command = format!("nix-collect-garbage --delete-older-than {}d", app.nix_keep_days);
+            match nix::collect_garbage(app.nix_keep_days) {
+                Ok(output) => {
+                    result.succeeded(command, output.freed);
+                    result.add_stderr(&output.stderr);
+                },
+                Err(err) => result.failed(command, err),
+            }
+
+            let shared = Arc::clone(&app.issues);
+            thread::spawn(move || {
+                let report = nix::scan(Path::new(&home_dir()));
+                if let Ok(mut data) = shared.lock() {
+                    data.nix = report;
+                }
+            });
+            result
+        },
+        _ => ActionResult::new("Nothing to do"),
     }
 }
 
-fn render_cleanup_menu(f: &mut Frame, app: &App, selected: usize) {
+/// Images of a runtime in the order the checklist shows them.
+fn docker_images(app: &App, runtime: usize) -> Vec<DockerImage> {
     let issues = app.issues.lock().unwrap();
-    
-    let area = f.area();
-    let popup_area = centered_rect(60, 50, area);
-    
-    f.render_widget(Block::default().style(Style::default().bg(Color::Black)), area);
-    
-    let block = Block::default()
-        .title("Cleanup Menu")
-        .borders(Borders::ALL)
-        .style(Style::default().bg(Color::Black));
-    
-    let inner = block.inner(popup_area);
-    f.render_widget(block, popup_area);
-    
-    let mut items = Vec::new();
-    
-    let nm_total: u64 = issues.node_modules.iter().map(|nm| nm.size).sum();
-    let nm_text = format!("Clean {} node_modules ({:.1}GB)", 
-        issues.node_modules.len(), 
-        bytes_to_gb(nm_total)
-    );
-    let nm_style = if selected == 0 {
-        Style::default().fg(Color::Black).bg(Color::White)
-    } else {
-        Style::default().fg(Color::White)
-    };
-    items.push(ListItem::new(nm_text).style(nm_style));
-    
-    let docker_text = format!("Prune Docker images ({})", issues.docker_images.len());
-    let docker_style = if selected == 1 {
-        Style::default().fg(Color::Black).bg(Color::White)
-    } else {
-        Style::default().fg(Color::White)
-    };
-    items.push(ListItem::new(docker_text).style(docker_style));
-    
-    let brew_text = "Clean Homebrew cache".to_string();
-    let brew_style = if selected == 2 {
-        Style::default().fg(Color::Black).bg(Color::White)
-    } else {
-        Style::default().fg(Color::White)
-    };
-    items.push(ListItem::new(brew_text).style(brew_style));
-    
-    let kill_text = "Kill heavy processes (free RAM)".to_string();
-    let kill_style = if selected == 3 {
-        Style::default().fg(Color::Black).bg(Color::White)
-    } else {
-        Style::default().fg(Color::White)
-    };
-    items.push(ListItem::new(kill_text).style(kill_style));
-    
-    items.push(ListItem::new(""));
-    items.push(ListItem::new("[↑/↓] Navigate  [Enter] Execute  [Esc] Cancel")
-        .style(Style::default().fg(Color::Gray)));
-    
-    let list = List::new(items);
-    f.render_widget(list, inner);
+    issues.runtimes.get(runtime).map_or_else(Vec::new, |r| {
+        r.usage.sorted_images(app.image_order).into_iter().cloned().collect()
+    })
 }
 
-fn render_kill_process_menu(f: &mut Frame, app: &App, selected: usize) {
+/// Images backing a container, running or stopped, or matching the keep-list are never removed.
+fn is_protected(app: &App, runtime: usize, image: &DockerImage) -> bool {
     let issues = app.issues.lock().unwrap();
-    
-    let area = f.area();
-    let popup_area = centered_rect(70, 60, area);
-    
-    f.render_widget(Block::default().style(Style::default().bg(Color::Black)), area);
-    
-    let block = Block::default()
-        .title("Kill Process (Free RAM)")
-        .borders(Borders::ALL)
-        .style(Style::default().bg(Color::Black));
-    
-    let inner = block.inner(popup_area);
-    f.render_widget(block, popup_area);
-    
-    let mut items = Vec::new();
-    
-    items.push(ListItem::new("Select a process to kill:")
-        .style(Style::default().fg(Color::Yellow)));
-    items.push(ListItem::new(""));
-    
-    for (i, proc) in issues.top_processes.iter().enumerate() {
-        let text = format!(
-            "{} - {} (CPU: {:.1}%, RAM: {:.1}GB, PID: {})",
-            i + 1,
-            proc.name,
-            proc.cpu,
-            bytes_to_gb(proc.memory),
-            proc.pid
-        );
-        
-        let style = if i == selected {
-            Style::default().fg(Color::Black).bg(Color::Red)
-        } else {
-            Style::default().fg(Color::White)
+    image.containers > 0 ||
+    issues
+        .runtimes
+        .get(runtime)
+        .is_some_and(|r| r.usage.containers_using(image).next().is_some()) ||
+    app.docker_keep.keeps(image)
+}
+
+fn remove_selected_images(app: &mut App, runtime: usize) -> ActionResult {
+    app.docker_failures.clear();
+    let selected = std::mem::take(&mut app.docker_selected);
+    let result = {
+        let mut issues = app.issues.lock().unwrap();
+        let Some(entry) = issues.runtimes.get_mut(runtime) else {
+            return ActionResult::new("Remove images");
         };
-        
-        items.push(ListItem::new(text).style(style));
-    }
-    
-    items.push(ListItem::new(""));
-    items.push(ListItem::new("WARNING: This will force kill the process!")
-        .style(Style::default().fg(Color::Red)));
-    items.push(ListItem::new("[↑/↓] Navigate  [Enter] Kill  [Esc] Back")
-        .style(Style::default().fg(Color::Gray)));
-    
-    let list = List::new(items);
-    f.render_widget(list, inner);
+        let engine = entry.runtime.clone();
+        let mut result = ActionResult::new(format!("Remove {} images", engine.name));
+        entry.usage.images.retain(|image| {
+            if !selected.contains(&image.id) {
+                return true;
+            }
+            match engine.remove_image(image) {
+                Ok(()) => {
+                    result.succeeded(image.name(), image.unique_size());
+                    false
+                },
+                Err(err) => {
+                    app.docker_failures.insert(image.id.clone(), err.to_string());
+                    result.failed(image.name(), err);
+                    true
+                },
+            }
+        });
+        result
+    };
+
+    rescan_runtimes(app);
+    result
 }
 
-fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
-    let popup_layout = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
+fn retention_plan(app: &App, runtime: usize) -> retention::RetentionPlan {
+    let issues = app.issues.lock().unwrap();
+    issues.runtimes.get(runtime).map_or_else(Default::default, |r| {
+        retention::plan(&r.usage, &app.retention, &app.docker_keep)
+    })
+}
+
+/// Removes exactly the tags and images of the confirmed `plan`. Bytes freed are only reported
+/// when every removal went through, since a failed tag can keep an image's layers alive.
+fn apply_retention(app: &mut App, runtime: usize, plan: &retention::RetentionPlan) -> ActionResult {
+    let Some(engine) = app.issues.lock().unwrap().runtimes.get(runtime).map(|r| r.runtime.clone()) else {
+        return ActionResult::new("Apply tag retention");
+    };
+
+    let failures = retention::apply(&engine, plan);
+    let mut result = ActionResult::new(format!("Apply {} tag retention", engine.name));
+    for name in plan.tags.iter().chain(&plan.dangling) {
+        if !failures.iter().any(|(failed, _)| failed == name) {
+            result.succeeded(name.clone(), 0);
+        }
+    }
+    for (name, err) in failures {
+        result.failed(name, err);
+    }
+    if result.failed.is_empty() {
+        result.bytes_freed = plan.bytes;
+    }
+
+    rescan_runtimes(app);
+    result
+}
+
+fn compose_projects(app: &App, runtime: usize) -> Vec<compose::ComposeProject> {
+    let issues = app.issues.lock().unwrap();
+    issues.runtimes.get(runtime).map_or_else(Vec::new, |r| compose::projects(&r.usage))
+}
+
+/// Tears down the project confirmed as `name`, looked up again by name since a rescan may
+/// have reordered or removed projects since the dialog opened.
+fn tear_down_project(app: &mut App, runtime: usize, name: &str) -> ActionResult {
+    let Some(engine) = app.issues.lock().unwrap().runtimes.get(runtime).map(|r| r.runtime.clone()) else {
+        return ActionResult::new("Tear down Compose project");
+    };
+    let mut result = ActionResult::new(format!("Tear down {} Compose project {name}", engine.name));
+    let Some(project) = compose_projects(app, runtime).into_iter().find(|project| project.name == name) else {
+        result.failed(name, "project no longer exists");
+        return result;
+    };
+
+    let failures = engine.tear_down(&project, &app.docker_keep);
+    if !project.is_running() {
+        let resources = project
+            .containers
+            .iter()
+            .map(|c| (c.name.as_str(), c.size))
+            .chain(project.networks.iter().map(|n| (n.name.as_str(), 0)))
+            .chain(project.volumes.iter().map(|v| (v.name.as_str(), v.size)))
+            .chain(
+                project
+                    .images
+                    .iter()
+                    .filter(|image| !app.docker_keep.keeps(image))
+                    .map(|image| (image.name(), image.unique_size())),
+            );
+        for (name, size) in resources {
+            if !failures.iter().any(|(failed, _)| failed == name) {
+                result.succeeded(name, size);
+            }
+        }
+    }
+    for (name, err) in failures {
+        result.failed(name, err);
+    }
+
+    rescan_runtimes(app);
+    result
+}
+
+fn prune_docker(runtime: &Runtime, row: CleanupRow, build_cache_hours: u64) -> ActionResult {
+    let name = runtime.name;
+    let (what, pruned) = match row {
+        CleanupRow::DockerContainers(_) => ("stopped containers", runtime.prune_containers()),
+        CleanupRow::DockerVolumes(_) => ("unused volumes", runtime.prune_volumes()),
+        CleanupRow::DockerBuildCache(_) => ("build cache", runtime.prune_build_cache(build_cache_hours)),
+        CleanupRow::DockerNetworks(_) => {
+            let mut result = ActionResult::new(format!("Prune unused {name} networks"));
+            match runtime.prune_networks() {
+                Ok(removed) => removed.into_iter().for_each(|network| result.succeeded(network, 0)),
+                Err(err) => result.failed("networks", err),
+            }
+            return result;
+        },
+        _ => return ActionResult::new("Prune"),
+    };
+
+    let mut result = ActionResult::new(format!("Prune {name} {what}"));
+    match pruned {
+        Ok(bytes) => result.succeeded(what, bytes),
+        Err(err) => result.failed(what, err),
+    }
+    result
+}
+
+fn cleanup_rows(issues: &IssuesData) -> Vec<CleanupRow> {
+    let mut rows = vec![
+        CleanupRow::NodeModules,
+        CleanupRow::DependencyDirs,
+    ];
+    for index in 0..issues.runtimes.len() {
+        rows.extend([
+            CleanupRow::DockerImages(index),
+            CleanupRow::DockerRetention(index),
+            CleanupRow::DockerContainers(index),
+            CleanupRow::DockerVolumes(index),
+            CleanupRow::DockerBuildCache(index),
+            CleanupRow::DockerNetworks(index),
+            CleanupRow::ComposeProjects(index),
+        ]);
+    }
+    rows.push(CleanupRow::Homebrew);
+    if issues.nix.is_some() {
+        rows.push(CleanupRow::Nix);
+    }
+    rows.push(CleanupRow::AppCaches);
+    rows.push(CleanupRow::KillProcess);
+    rows.extend((0..issues.system_caches.len()).map(CleanupRow::SystemCache));
+    rows.extend((0..issues.categories.len()).map(CleanupRow::Category));
+    rows
+}
+
+fn cleanup_row(app: &App, selected: usize) -> Option<CleanupRow> {
+    cleanup_rows(&app.issues.lock().unwrap()).get(selected).copied()
+}
+
+fn cleanup_menu_len(app: &App) -> usize {
+    cleanup_rows(&app.issues.lock().unwrap()).len()
+}
+
+/// Leaves the alternate screen while `run` executes, so `sudo` can prompt on the terminal.
+fn with_suspended_tui<T>(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    run: impl FnOnce() -> T,
+) -> io::Result<T> {
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    let result = run();
+    enable_raw_mode()?;
+    execute!(terminal.backend_mut(), EnterAlternateScreen)?;
+    terminal.clear()?;
+    Ok(result)
+}
+
+fn clean_system_cache(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    app: &mut App,
+    index: usize,
+) -> io::Result<ActionResult> {
+    let Some(cache) = app.issues.lock().unwrap().system_caches.get(index).cloned() else {
+        return Ok(ActionResult::new("Clean system cache"));
+    };
+
+    // Only stderr is captured, so `sudo` can still prompt and the tool's progress stays visible.
+    let output = with_suspended_tui(terminal, || {
+        println!("macmon needs administrator rights to run: {}", cache.command.join(" "));
+        cache
+            .privileged_command()
+            .stdin(Stdio::inherit())
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::piped())
+            .output()
+    })?;
+
+    let mut issues = app.issues.lock().unwrap();
+    let mut result = ActionResult::new(format!("Clean {}", cache.name));
+    let Some(cache) = issues.system_caches.get_mut(index) else {
+        return Ok(result);
+    };
+    let before = cache.size;
+    cache.refresh_size();
+
+    let command = cache.command.join(" ");
+    match output {
+        Ok(output) => {
+            result.add_stderr(&String::from_utf8_lossy(&output.stderr));
+            if output.status.success() {
+                result.succeeded(command, before.saturating_sub(cache.size));
+            } else {
+                result.failed(command, format!("exited with {}", output.status));
+            }
+        },
+        Err(err) => result.failed(command, format!("could not run {}: {err}", cache.command[0])),
+    }
+
+    Ok(result)
+}
+
+/// A file or directory offered in a cleanup checklist.
+struct ChecklistItem {
+    finding: Finding,
+    /// Why the item cannot be picked, such as its app running.
+    blocked: Option<String>,
+}
+
+/// Rows whose Enter opens a checklist of their findings instead of acting at once.
+fn has_checklist(row: CleanupRow) -> bool {
+    matches!(
+        row,
+        CleanupRow::NodeModules | CleanupRow::DependencyDirs | CleanupRow::AppCaches | CleanupRow::Category(_)
+    )
+}
+
+fn checklist_title(issues: &IssuesData, row: CleanupRow) -> String {
+    match row {
+        CleanupRow::NodeModules => "node_modules".to_string(),
+        CleanupRow::DependencyDirs => "CocoaPods/Carthage/SwiftPM dirs".to_string(),
+        CleanupRow::AppCaches => "App caches".to_string(),
+        CleanupRow::Category(index) => issues.categories.get(index).map_or_else(String::new, |c| c.name.to_string()),
+        _ => String::new(),
+    }
+}
+
+fn checklist_items(app: &App, row: CleanupRow) -> Vec<ChecklistItem> {
+    let issues = app.issues.lock().unwrap();
+    let available = |finding: &Finding| ChecklistItem { finding: finding.clone(), blocked: None };
+
+    match row {
+        CleanupRow::NodeModules => issues
+            .node_modules
+            .iter()
+            .map(|nm| ChecklistItem {
+                finding: Finding {
+                    label: nm.path.display().to_string(),
+                    path: nm.path.clone(),
+                    size: nm.size,
+                    last_used: None,
+                    removal: Removal::DeletePath,
+                    companions: Vec::new(),
+                },
+                blocked: None,
+            })
+            .collect(),
+        CleanupRow::DependencyDirs => issues.dependency_dirs.iter().map(available).collect(),
+        CleanupRow::AppCaches => issues
+            .app_caches
+            .iter()
+            .flat_map(|cache| {
+                let blocked = app.is_app_running(cache).then(|| format!("{} is running", cache.app));
+                cache.caches.iter().map(move |finding| ChecklistItem {
+                    finding: finding.clone(),
+                    blocked: blocked.clone(),
+                })
+            })
+            .collect(),
+        CleanupRow::Category(index) => issues
+            .categories
+            .get(index)
+            .map_or_else(Vec::new, |c| c.findings.iter().map(available).collect()),
+        _ => Vec::new(),
+    }
+}
+
+/// Space toggles the item under the cursor, `a` ticks every item, `u` none and `i` inverts
+/// the selection. Blocked items are never ticked.
+fn update_checked(app: &mut App, row: CleanupRow, selected: usize, key: KeyCode) {
+    let items = checklist_items(app, row);
+    let available = items.iter().filter(|item| item.blocked.is_none()).map(|item| &item.finding.path);
+    match key {
+        KeyCode::Char(' ') => {
+            if let Some(item) = items.get(selected)
+                && item.blocked.is_none()
+                && !app.checked.remove(&item.finding.path)
+            {
+                app.checked.insert(item.finding.path.clone());
+            }
+        },
+        KeyCode::Char('a') => {
+            app.checked = available.cloned().collect();
+        },
+        KeyCode::Char('u') => {
+            app.checked.clear();
+        },
+        KeyCode::Char('i') => {
+            app.checked = available.filter(|path| !app.checked.contains(*path)).cloned().collect();
+        },
+        _ => {}
+    }
+}
+
+/// The findings ticked in a checklist, skipping any that became blocked since.
+fn checked_findings(app: &App, row: CleanupRow) -> Vec<Finding> {
+    checklist_items(app, row)
+        .into_iter()
+        .filter(|item| item.blocked.is_none() && app.checked.contains(&item.finding.path))
+        .map(|item| item.finding)
+        .collect()
+}
+
+/// Opens the checklist for `row`. Rows that used to clean everything at once start with
+/// every item ticked; categories are reviewed one item at a time, so they start empty.
+fn open_checklist(app: &mut App, row: CleanupRow) {
+    app.checked = if matches!(row, CleanupRow::Category(_)) {
+        HashSet::new()
+    } else {
+        checklist_items(app, row)
+            .into_iter()
+            .filter(|item| item.blocked.is_none())
+            .map(|item| item.finding.path)
+            .collect()
+    };
+    app.mode = AppMode::Checklist { row, selected: 0 };
+}
+
+fn remove_checked(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    app: &mut App,
+    row: CleanupRow,
+) -> io::Result<ActionResult> {
+    let findings = checked_findings(app, row);
+    let deletion = app.deletion;
+    let remove_all = || -> Vec<io::Result<u64>> {
+        findings.iter().map(|finding| finding.remove(deletion)).collect()
+    };
+    let results = if findings.iter().any(Finding::needs_root) {
+        with_suspended_tui(terminal, || {
+            println!("macmon needs administrator rights to remove some of the selected items");
+            remove_all()
+        })?
+    } else {
+        remove_all()
+    };
+
+    let verb = match deletion {
+        Deletion::Trash => "Move to the trash",
+        Deletion::Permanent => "Delete",
+    };
+    let mut result = ActionResult::new(format!("{verb}: {}", checklist_title(&app.issues.lock().unwrap(), row)));
+    let mut removed = HashSet::new();
+    for (finding, outcome) in findings.iter().zip(results) {
+        match outcome {
+            Ok(bytes) => {
+                removed.insert(finding.path.clone());
+                let item = finding.path.display().to_string();
+                match (deletion, &finding.removal) {
+                    (Deletion::Trash, Removal::DeletePath) => result.trashed(item, bytes),
+                    _ => result.succeeded(item, bytes),
+                }
+            },
+            Err(err) => result.failed(finding.path.display().to_string(), err),
+        }
+    }
+
+    {
+        let mut issues = app.issues.lock().unwrap();
+        issues.node_modules.retain(|nm| !removed.contains(&nm.path));
+        issues.dependency_dirs.retain(|dir| !removed.contains(&dir.path));
+        for cache in issues.app_caches.iter_mut() {
+            cache.caches.retain(|finding| !removed.contains(&finding.path));
+        }
+        issues.app_caches.retain(|cache| !cache.caches.is_empty());
+        for category in issues.categories.iter_mut() {
+            category.findings.retain(|finding| !removed.contains(&finding.path));
+        }
+    }
+    app.checked.retain(|path| !removed.contains(path));
+
+    Ok(result)
+}
+
+fn kill_process(app: &App, pid: u32) -> ActionResult {
+    let process = app.issues.lock().unwrap().top_processes.iter().find(|p| p.pid == pid).cloned();
+    let name = match process {
+        Some(process) => format!("{} (pid {pid})", process.name),
+        None => format!("pid {pid}"),
+    };
+    let mut result = ActionResult::new(format!("Kill {name}"));
+    
+    match std::process::Command::new("kill").arg("-9").arg(pid.to_string()).output() {
+        Ok(output) if output.status.success() => result.succeeded(name, 0),
+        Ok(output) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            result.add_stderr(&stderr);
+            result.failed(name, format!("kill exited with {}: {}", output.status, stderr.trim()));
+        },
+        Err(err) => result.failed(name, format!("could not run kill: {err}")),
+    }
+    
+    result
+}
+
+fn ui(f: &mut Frame, app: &App) {
+    match &app.mode {
+        AppMode::Normal => {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Length(10),
+                    Constraint::Min(5),
+                    Constraint::Length(3),
+                ])
+                .split(f.area());
+
+            render_metrics(f, app, chunks[0]);
+            render_issues(f, app, chunks[1]);
+            render_help(f, app, chunks[2]);
+        },
+        AppMode::CleanupMenu { selected } => {
+            render_cleanup_menu(f, app, *selected);
+        },
+        AppMode::KillProcessMenu { selected } => {
+            render_kill_process_menu(f, app, *selected);
+        },
+        AppMode::Checklist { row, selected } => {
+            render_checklist(f, app, *row, *selected);
+        },
+        AppMode::DockerImagesMenu { runtime, selected } => {
+            render_docker_images_menu(f, app, *runtime, *selected);
+        },
+        AppMode::RetentionPreview { runtime } => {
+            render_retention_preview(f, app, *runtime);
+        },
+        AppMode::ComposeMenu { runtime, selected } => {
+            render_compose_menu(f, app, *runtime, *selected);
+        },
+        AppMode::DryRun { plan, offset, .. } => {
+            render_dry_run(f, plan, *offset);
+        },
+        AppMode::Confirm { action, plan, typed, offset, .. } => {
+            render_confirm(f, action, plan, typed.as_deref(), *offset);
+        },
+        AppMode::Results { index, offset, .. } => {
+            if let Some(result) = app.history.get(*index) {
+                render_results(f, result, *index, app.history.len(), *offset);
+            }
+        }
+    }
+}
+
+fn render_cleanup_menu(f: &mut Frame, app: &App, selected: usize) {
+    let issues = app.issues.lock().unwrap();
+    
+    let area = f.area();
+    let popup_area = centered_rect(60, 50, area);
+    
+    f.render_widget(Block::default().style(Style::default().bg(Color::Black)), area);
+    
+    let block = Block::default()
+        .title(match (app.dry_run, app.deletion) {
+            (true, _) => "Cleanup Menu (dry run)",
+            (false, Deletion::Trash) => "Cleanup Menu (files go to the trash)",
+            (false, Deletion::Permanent) => "Cleanup Menu (files are deleted permanently)",
+        })
+        .borders(Borders::ALL)
+        .style(Style::default().bg(Color::Black));
+    
+    let inner = block.inner(popup_area);
+    f.render_widget(block, popup_area);
+    
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(2)])
+        .split(inner);
+    
+    // Every container runtime adds its own rows, so the list scrolls to keep the selection shown.
+    let items: Vec<ListItem> = cleanup_rows(&issues)
+        .into_iter()
+        .map(|row| ListItem::new(cleanup_row_text(app, &issues, row)).style(Style::default().fg(Color::White)))
+        .collect();
+    let list = List::new(items).highlight_style(Style::default().fg(Color::Black).bg(Color::White));
+    let mut state = ListState::default().with_selected(Some(selected));
+    f.render_stateful_widget(list, chunks[0], &mut state);
+    
+    let footer = List::new(vec![
+        ListItem::new(""),
+        ListItem::new("[↑/↓] Navigate  [Enter] Execute  [n] Preview  [d] Dry run  [x] Trash/permanent  [Esc] Cancel")
+            .style(Style::default().fg(Color::Gray)),
+    ]);
+    f.render_widget(footer, chunks[1]);
+}
+
+fn cleanup_row_text(app: &App, issues: &IssuesData, row: CleanupRow) -> String {
+    match row {
+        CleanupRow::NodeModules => {
+            let total: u64 = issues.node_modules.iter().map(|nm| nm.size).sum();
+            format!("Clean {} node_modules ({})", issues.node_modules.len(), format_size(total))
+        },
+        CleanupRow::DependencyDirs => {
+            let total: u64 = issues.dependency_dirs.iter().map(|d| d.size).sum();
+            format!(
+                "Clean {} CocoaPods/Carthage/SwiftPM dirs ({})",
+                issues.dependency_dirs.len(),
+                format_size(total)
+            )
+        },
+        CleanupRow::DockerImages(index) => {
+            let entry = &issues.runtimes[index];
+            format!(
+                "Choose {} images to remove ({} images, {} reclaimable)",
+                entry.runtime.name,
+                entry.usage.images.len(),
+                format_size(entry.usage.reclaimable_images())
+            )
+        },
+        CleanupRow::DockerRetention(index) => {
+            let entry = &issues.runtimes[index];
+            let plan = retention::plan(&entry.usage, &app.retention, &app.docker_keep);
+            format!(
+                "Apply {} tag retention (keep newest {}: {} tags, {} dangling, {}) [←/→] tags",
+                entry.runtime.name,
+                app.retention.keep_newest,
+                plan.tags.len(),
+                plan.dangling.len(),
+                format_size(plan.bytes)
+            )
+        },
+        CleanupRow::DockerContainers(index) => {
+            let entry = &issues.runtimes[index];
+            let stopped: Vec<_> = entry.usage.stopped_containers().collect();
+            format!(
+                "Prune stopped {} containers ({}, {})",
+                entry.runtime.name,
+                stopped.len(),
+                format_size(stopped.iter().map(|c| c.size).sum())
+            )
+        },
+        CleanupRow::DockerVolumes(index) => {
+            let entry = &issues.runtimes[index];
+            let unused: Vec<_> = entry.usage.unused_volumes().collect();
+            format!(
+                "Prune unused {} volumes ({}, {})",
+                entry.runtime.name,
+                unused.len(),
+                format_size(unused.iter().map(|v| v.size).sum())
+            )
+        },
+        CleanupRow::DockerBuildCache(index) => {
+            let entry = &issues.runtimes[index];
+            let old: Vec<_> = entry.usage.build_cache_older_than(app.build_cache_hours).collect();
+            format!(
+                "Prune {} build cache older than {}h ({} records, {}) [←/→] hours",
+                entry.runtime.name,
+                app.build_cache_hours,
+                old.len(),
+                format_size(old.iter().map(|r| r.size).sum())
+            )
+        },
+        CleanupRow::DockerNetworks(index) => {
+            let entry = &issues.runtimes[index];
+            format!(
+                "Prune unused {} networks ({})",
+                entry.runtime.name,
+                entry.usage.unused_networks().len()
+            )
+        },
+        CleanupRow::ComposeProjects(index) => {
+            let entry = &issues.runtimes[index];
+            let projects = compose::projects(&entry.usage);
+            let stopped: Vec<_> = projects.iter().filter(|p| !p.is_running()).collect();
+            format!(
+                "Tear down {} Compose projects ({} of {} stopped, {})",
+                entry.runtime.name,
+                stopped.len(),
+                projects.len(),
+                format_size(stopped.iter().map(|p| p.size()).sum())
+            )
+        },
+        CleanupRow::Homebrew => match &issues.homebrew {
+            Some(brew) => format!(
+                "Clean Homebrew ({} items, {})",
+                brew.removable.len(),
+                format_size(brew.reclaimable)
+            ),
+            None => "Clean Homebrew (not installed)".to_string(),
+        },
+        CleanupRow::Nix => {
+            let old = issues
+                .nix
+                .as_ref()
+                .map_or(0, |nix| nix.generations_older_than(app.nix_keep_days).len());
+            format!(
+                "Collect Nix garbage ({old} generations older than {}d) [←/→] days",
+                app.nix_keep_days
+            )
+        },
+        CleanupRow::AppCaches => {
+            let (closed, running): (Vec<&AppCache>, Vec<&AppCache>) =
+                issues.app_caches.iter().partition(|cache| !app.is_app_running(cache));
+            let closed_total: u64 = closed.iter().map(|cache| cache.size()).sum();
+            format!(
+                "Clear caches of closed apps ({}, {} running skipped)",
+                format_size(closed_total),
+                running.len()
+            )
+        },
+        CleanupRow::KillProcess => "Kill heavy processes (free RAM)".to_string(),
+        CleanupRow::SystemCache(index) => {
+            let cache = &issues.system_caches[index];
+            format!("Clean {} ({}, needs root)", cache.name, format_size(cache.size))
+        },
+        CleanupRow::Category(index) => {
+            let category = &issues.categories[index];
+            format!(
+                "Review {} ({} items, {})",
+                category.name,
+                category.findings.len(),
+                format_size(category.total_size())
+            )
+        },
+    }
+}
+
+fn render_kill_process_menu(f: &mut Frame, app: &App, selected: usize) {
+    let issues = app.issues.lock().unwrap();
+    
+    let area = f.area();
+    let popup_area = centered_rect(70, 60, area);
+    
+    f.render_widget(Block::default().style(Style::default().bg(Color::Black)), area);
+    
+    let block = Block::default()
+        .title("Kill Process (Free RAM)")
+        .borders(Borders::ALL)
+        .style(Style::default().bg(Color::Black));
+    
+    let inner = block.inner(popup_area);
+    f.render_widget(block, popup_area);
+    
+    let mut items = Vec::new();
+    
+    items.push(ListItem::new("Select a process to kill:")
+        .style(Style::default().fg(Color::Yellow)));
+    items.push(ListItem::new(""));
+    
+    for (i, proc) in issues.top_processes.iter().enumerate() {
+        let text = format!(
+            "{} - {} (CPU: {:.1}%, RAM: {}, PID: {})",
+            i + 1,
+            proc.name,
+            proc.cpu,
+            format_size(proc.memory),
+            proc.pid
+        );
+        
+        let style = if i == selected {
+            Style::default().fg(Color::Black).bg(Color::Red)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        
+        items.push(ListItem::new(text).style(style));
+    }
+    
+    items.push(ListItem::new(""));
+    items.push(ListItem::new("WARNING: This will force kill the process!")
+        .style(Style::default().fg(Color::Red)));
+    items.push(ListItem::new("[↑/↓] Navigate  [Enter] Kill  [n] Preview  [Esc] Back")
+        .style(Style::default().fg(Color::Gray)));
+    
+    let list = List::new(items);
+    f.render_widget(list, inner);
+}
+
+fn render_checklist(f: &mut Frame, app: &App, row: CleanupRow, selected: usize) {
+    let items = checklist_items(app, row);
+    let (title, note) = {
+        let issues = app.issues.lock().unwrap();
+        let note = match row {
+            CleanupRow::Category(index) => issues.categories.get(index).and_then(|c| c.note.clone()),
+            _ => None,
+        };
+        (checklist_title(&issues, row), note)
+    };
+    let checked = checked_findings(app, row);
+    
+    let area = f.area();
+    let popup_area = centered_rect(80, 70, area);
+    
+    f.render_widget(Block::default().style(Style::default().bg(Color::Black)), area);
+    
+    let block = Block::default()
+        .title(format!(
+            "{title} ({} of {} selected, {})",
+            checked.len(),
+            items.len(),
+            format_size(checked.iter().map(|finding| finding.size).sum())
+        ))
+        .borders(Borders::ALL)
+        .style(Style::default().bg(Color::Black));
+    
+    let inner = block.inner(popup_area);
+    f.render_widget(block, popup_area);
+    
+    let mut list_items = Vec::new();
+    
+    if let Some(note) = note {
+        list_items.push(ListItem::new(note).style(Style::default().fg(Color::Cyan)));
+        list_items.push(ListItem::new(""));
+    }
+    
+    if items.is_empty() {
+        list_items.push(ListItem::new("Nothing left to clean").style(Style::default().fg(Color::Green)));
+    }
+    
+    for (i, item) in items.iter().enumerate() {
+        let finding = &item.finding;
+        let checkbox = if app.checked.contains(&finding.path) { "[x]" } else { "[ ]" };
+        let mut text = match finding.last_used {
+            Some(_) => format!(
+                "{checkbox} {} ({}, {})",
+                finding.label,
+                format_size(finding.size),
+                format_last_used(finding.last_used)
+            ),
+            None => format!("{checkbox} {} ({})", finding.label, format_size(finding.size)),
+        };
+        let mut color = Color::White;
+        if let Some(reason) = &item.blocked {
+            text.push_str(&format!(" - {reason}"));
+            color = Color::DarkGray;
+        }
+        
+        let style = if i == selected {
+            Style::default().fg(Color::Black).bg(Color::Red)
+        } else {
+            Style::default().fg(color)
+        };
+        
+        list_items.push(ListItem::new(text).style(style));
+    }
+    
+    list_items.push(ListItem::new(""));
+    if let Some(item) = items.get(selected) {
+        list_items.push(ListItem::new(item.finding.path.display().to_string())
+            .style(Style::default().fg(Color::Gray)));
+    }
+    list_items.push(ListItem::new("[↑/↓] Navigate  [Space] Select  [a] All  [u] None  [i] Invert  [Enter] Remove selected  [n] Preview  [Esc] Back")
+        .style(Style::default().fg(Color::Gray)));
+    
+    let list = List::new(list_items);
+    f.render_widget(list, inner);
+}
+
+fn render_docker_images_menu(f: &mut Frame, app: &App, runtime: usize, selected: usize) {
+    let images = docker_images(app, runtime);
+    let name = app.issues.lock().unwrap().runtimes.get(runtime).map_or("Docker", |r| r.runtime.name);
+    let selected_total: u64 = images
+        .iter()
+        .filter(|image| app.docker_selected.contains(&image.id))
+        .map(DockerImage::unique_size)
+        .sum();
+    
+    let area = f.area();
+    let popup_area = centered_rect(80, 70, area);
+    
+    f.render_widget(Block::default().style(Style::default().bg(Color::Black)), area);
+    
+    let block = Block::default()
+        .title(format!(
+            "{name} images ({} selected, {})",
+            app.docker_selected.len(),
+            format_size(selected_total)
+        ))
+        .borders(Borders::ALL)
+        .style(Style::default().bg(Color::Black));
+    
+    let inner = block.inner(popup_area);
+    f.render_widget(block, popup_area);
+    
+    let mut items = Vec::new();
+    
+    if images.is_empty() {
+        items.push(ListItem::new(format!("No {name} images")).style(Style::default().fg(Color::Green)));
+    }
+    
+    for (i, image) in images.iter().enumerate() {
+        let checkbox = if app.docker_selected.contains(&image.id) { "[x]" } else { "[ ]" };
+        let mut text = format!(
+            "{checkbox} {} ({}, {} unique, created {})",
+            image.name(),
+            format_size(image.size),
+            format_size(image.unique_size()),
+            format_age(image.created)
+        );
+        
+        let users: Vec<String> = {
+            let issues = app.issues.lock().unwrap();
+            issues.runtimes.get(runtime).map_or_else(Vec::new, |r| {
+                r.usage
+                    .containers_using(image)
+                    .map(|c| format!("{} ({})", c.name, if c.running { "running" } else { "stopped" }))
+                    .collect()
+            })
+        };
+        let mut color = Color::White;
+        if let Some(err) = app.docker_failures.get(&image.id) {
+            text.push_str(&format!(" - failed: {err}"));
+            color = Color::Red;
+        } else if !users.is_empty() {
+            text.push_str(&format!(" - used by {}", users.join(", ")));
+            color = Color::DarkGray;
+        } else if image.containers > 0 {
+            text.push_str(" - used by a container");
+            color = Color::DarkGray;
+        } else if app.docker_keep.keeps(image) {
+            text.push_str(" - kept");
+            color = Color::DarkGray;
+        }
+        
+        let style = if i == selected {
+            Style::default().fg(Color::Black).bg(Color::Red)
+        } else {
+            Style::default().fg(color)
+        };
+        
+        items.push(ListItem::new(text).style(style));
+    }
+    
+    items.push(ListItem::new(""));
+    items.push(ListItem::new("[↑/↓] Navigate  [Space] Select  [p] Keep repository  [Enter] Remove selected  [n] Preview  [Esc] Back")
+        .style(Style::default().fg(Color::Gray)));
+    
+    let list = List::new(items);
+    f.render_widget(list, inner);
+}
+
+fn render_retention_preview(f: &mut Frame, app: &App, runtime: usize) {
+    let plan = retention_plan(app, runtime);
+    let name = app.issues.lock().unwrap().runtimes.get(runtime).map_or("Docker", |r| r.runtime.name);
+    
+    let area = f.area();
+    let popup_area = centered_rect(70, 60, area);
+    
+    f.render_widget(Block::default().style(Style::default().bg(Color::Black)), area);
+    
+    let block = Block::default()
+        .title(format!("{name} tag retention ({})", format_size(plan.bytes)))
+        .borders(Borders::ALL)
+        .style(Style::default().bg(Color::Black));
+    
+    let inner = block.inner(popup_area);
+    f.render_widget(block, popup_area);
+    
+    let mut items = vec![
+        ListItem::new(format!(
+            "Keep the newest {} tags per repository and tags matching {}{}",
+            app.retention.keep_newest,
+            app.retention.keep_tags.join(", "),
+            if app.retention.remove_dangling { ", remove dangling images" } else { "" }
+        )).style(Style::default().fg(Color::Cyan)),
+        ListItem::new(""),
+    ];
+    
+    if plan.is_empty() {
+        items.push(ListItem::new("Nothing to remove").style(Style::default().fg(Color::Green)));
+    }
+    for tag in &plan.tags {
+        items.push(ListItem::new(format!("  untag {tag}")).style(Style::default().fg(Color::White)));
+    }
+    for id in &plan.dangling {
+        items.push(ListItem::new(format!("  remove dangling {id}")).style(Style::default().fg(Color::White)));
+    }
+    
+    items.push(ListItem::new(""));
+    items.push(ListItem::new("[Enter] Apply  [n] Preview  [Esc] Back")
+        .style(Style::default().fg(Color::Gray)));
+    
+    let list = List::new(items);
+    f.render_widget(list, inner);
+}
+
+fn render_compose_menu(f: &mut Frame, app: &App, runtime: usize, selected: usize) {
+    let projects = compose_projects(app, runtime);
+    let name = app.issues.lock().unwrap().runtimes.get(runtime).map_or("Docker", |r| r.runtime.name);
+    
+    let area = f.area();
+    let popup_area = centered_rect(80, 60, area);
+    
+    f.render_widget(Block::default().style(Style::default().bg(Color::Black)), area);
+    
+    let block = Block::default()
+        .title(format!("{name} Compose projects"))
+        .borders(Borders::ALL)
+        .style(Style::default().bg(Color::Black));
+    
+    let inner = block.inner(popup_area);
+    f.render_widget(block, popup_area);
+    
+    let mut items = Vec::new();
+    
+    if projects.is_empty() {
+        items.push(ListItem::new("No Compose projects").style(Style::default().fg(Color::Green)));
+    }
+    
+    for (i, project) in projects.iter().enumerate() {
+        let text = format!(
+            "{} ({}, {} containers, {} images, {} volumes, {} networks{})",
+            project.name,
+            format_size(project.size()),
+            project.containers.len(),
+            project.images.len(),
+            project.volumes.len(),
+            project.networks.len(),
+            if project.is_running() { ", running" } else { "" }
+        );
+        
+        let style = if i == selected {
+            Style::default().fg(Color::Black).bg(Color::Red)
+        } else if project.is_running() {
+            Style::default().fg(Color::DarkGray)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        
+        items.push(ListItem::new(text).style(style));
+    }
+    
+    items.push(ListItem::new(""));
+    if let Some(project) = projects.get(selected).filter(|project| project.is_running()) {
+        items.push(ListItem::new(format!("{} is running: stop the project first", project.name))
+            .style(Style::default().fg(Color::Yellow)));
+    }
+    items.push(ListItem::new("Removes containers, networks, volumes and images only the project uses")
+        .style(Style::default().fg(Color::Cyan)));
+    items.push(ListItem::new("[↑/↓] Navigate  [Enter] Tear down stopped project  [n] Preview  [Esc] Back")
+        .style(Style::default().fg(Color::Gray)));
+    
+    let list = List::new(items);
+    f.render_widget(list, inner);
+}
+
+fn render_dry_run(f: &mut Frame, plan: &CleanupPlan, offset: usize) {
+    let verb = match plan.deletion {
+        Some(Deletion::Trash) => "move to the trash",
+        Some(Deletion::Permanent) => "permanently delete",
+        None => "remove",
+    };
+    let summary = if plan.deletion.is_none() && plan.trashed > 0 {
+        format!(
+            "Would remove {} items, moving {} to the trash and freeing {} (nothing has been touched)",
+            plan.items.len(),
+            format_size(plan.trashed),
+            format_size(plan.permanent_bytes())
+        )
+    } else {
+        format!(
+            "Would {verb} {} items and free {} (nothing has been touched)",
+            plan.items.len(),
+            format_size(plan.bytes)
+        )
+    };
+    let footer = vec![ListItem::new("[↑/↓] Scroll  [Esc] Back").style(Style::default().fg(Color::Gray))];
+    render_plan(f, &format!("Dry run: {}", plan.action), summary, plan, offset, footer);
+}
+
+fn render_confirm(f: &mut Frame, action: &PendingAction, plan: &CleanupPlan, typed: Option<&str>, offset: usize) {
+    let summary = match (action, plan.deletion) {
+        (PendingAction::Kill { .. }, _) => format!(
+            "Kill {} processes using {} of memory",
+            plan.items.len(),
+            format_size(plan.bytes)
+        ),
+        (_, Some(Deletion::Trash)) => format!(
+            "Move {} items ({}) to the trash",
+            plan.items.len(),
+            format_size(plan.bytes)
+        ),
+        _ if plan.trashed > 0 => format!(
+            "Remove {} items: move {} to the trash and permanently free {}",
+            plan.items.len(),
+            format_size(plan.trashed),
+            format_size(plan.permanent_bytes())
+        ),
+        _ => format!(
+            "Permanently remove {} items, freeing {}",
+            plan.items.len(),
+            format_size(plan.bytes)
+        ),
+    };
+    let footer = match typed {
+        Some(typed) => vec![
+            ListItem::new(format!("This cannot be undone. Type yes and press Enter to continue: {typed}_"))
+                .style(Style::default().fg(Color::Red)),
+            ListItem::new("[↑/↓] Scroll  [Esc] Cancel").style(Style::default().fg(Color::Gray)),
+        ],
+        None => vec![ListItem::new("[y] Confirm  [↑/↓] Scroll  [n/Esc] Cancel").style(Style::default().fg(Color::Gray))],
+    };
+    render_plan(f, &format!("Confirm: {}", plan.action), summary, plan, offset, footer);
+}
+
+/// The detail lines of a result: failures first, then what went through and any stderr.
+fn result_lines(result: &ActionResult) -> Vec<(String, Color)> {
+    let mut lines = Vec::new();
+    if !result.failed.is_empty() {
+        lines.push((format!("Failed ({}):", result.failed.len()), Color::Red));
+        lines.extend(result.failed.iter().map(|(item, err)| (format!("  {item}: {err}"), Color::Red)));
+    }
+    if !result.succeeded.is_empty() {
+        lines.push((format!("Done ({}):", result.succeeded.len()), Color::Green));
+        lines.extend(result.succeeded.iter().map(|item| (format!("  {item}"), Color::White)));
+    }
+    if !result.stderr.is_empty() {
+        lines.push(("stderr:".to_string(), Color::Gray));
+        lines.extend(result.stderr.lines().map(|line| (format!("  {line}"), Color::Gray)));
+    }
+    lines
+}
+
+fn render_results(f: &mut Frame, result: &ActionResult, index: usize, count: usize, offset: usize) {
+    let area = f.area();
+    let popup_area = centered_rect(80, 70, area);
+    
+    f.render_widget(Block::default().style(Style::default().bg(Color::Black)), area);
+    
+    let block = Block::default()
+        .title(format!("Result {}/{count}: {} ({})", index + 1, result.action, format_age(result.finished)))
+        .borders(Borders::ALL)
+        .style(Style::default().bg(Color::Black));
+    
+    let inner = block.inner(popup_area);
+    f.render_widget(block, popup_area);
+    
+    let color = if result.failed.is_empty() { Color::Green } else { Color::Red };
+    let mut items = vec![
+        ListItem::new(format!(
+            "{} succeeded, {} failed, {}",
+            result.succeeded.len(),
+            result.failed.len(),
+            result.space()
+        )).style(Style::default().fg(color)),
+        ListItem::new(""),
+    ];
+    
+    let lines = result_lines(result);
+    let visible = (inner.height as usize).saturating_sub(5);
+    for (line, color) in lines.iter().skip(offset).take(visible) {
+        items.push(ListItem::new(line.as_str()).style(Style::default().fg(*color)));
+    }
+    
+    let hidden = lines.len().saturating_sub(offset + visible);
+    items.push(ListItem::new(if hidden > 0 { format!("… {hidden} more") } else { String::new() })
+        .style(Style::default().fg(Color::DarkGray)));
+    items.push(ListItem::new("[↑/↓] Scroll  [←/→] Older/newer  [Esc] Close")
+        .style(Style::default().fg(Color::Gray)));
+    
+    let list = List::new(items);
+    f.render_widget(list, inner);
+}
+
+/// A popup listing a plan's items below `summary`, scrolled to `offset`, with `footer` last.
+fn render_plan(
+    f: &mut Frame,
+    title: &str,
+    summary: String,
+    plan: &CleanupPlan,
+    offset: usize,
+    footer: Vec<ListItem>,
+) {
+    let area = f.area();
+    let popup_area = centered_rect(80, 70, area);
+    
+    f.render_widget(Block::default().style(Style::default().bg(Color::Black)), area);
+    
+    let block = Block::default()
+        .title(title.to_string())
+        .borders(Borders::ALL)
+        .style(Style::default().bg(Color::Black));
+    
+    let inner = block.inner(popup_area);
+    f.render_widget(block, popup_area);
+    
+    let mut items = vec![
+        ListItem::new(summary).style(Style::default().fg(Color::Yellow)),
+        ListItem::new(""),
+    ];
+    
+    if plan.is_empty() {
+        items.push(ListItem::new("Nothing to remove").style(Style::default().fg(Color::Green)));
+    }
+    
+    let visible = (inner.height as usize).saturating_sub(4 + footer.len());
+    for (name, size) in plan.items.iter().skip(offset).take(visible) {
+        let text = if *size > 0 {
+            format!("{name} ({})", format_size(*size))
+        } else {
+            name.clone()
+        };
+        items.push(ListItem::new(text).style(Style::default().fg(Color::White)));
+    }
+    
+    let hidden = plan.items.len().saturating_sub(offset + visible);
+    items.push(ListItem::new(if hidden > 0 { format!("… {hidden} more") } else { String::new() })
+        .style(Style::default().fg(Color::DarkGray)));
+    items.extend(footer);
+    
+    let list = List::new(items);
+    f.render_widget(list, inner);
+}
+
+fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
+    let popup_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
             Constraint::Percentage((100 - percent_y) / 2),
             Constraint::Percentage(percent_y),
             Constraint::Percentage((100 - percent_y) / 2),
@@ -643,45 +2182,240 @@ fn render_issues(f: &mut Frame, app: &App, area: Rect) {
         if !issues.node_modules.is_empty() {
             let total_size: u64 = issues.node_modules.iter().map(|nm| nm.size).sum();
             items.push(ListItem::new(format!(
-                "• node_modules: {:.1}GB in {} projects",
-                bytes_to_gb(total_size),
+                "• node_modules: {} in {} projects",
+                format_size(total_size),
                 issues.node_modules.len()
             )).style(Style::default().fg(Color::Red)));
 
             for (i, nm) in issues.node_modules.iter().take(3).enumerate() {
                 items.push(ListItem::new(format!(
-                    "  {}. {} ({:.1}GB)",
+                    "  {}. {} ({})",
                     i + 1,
                     nm.path.display(),
-                    bytes_to_gb(nm.size)
+                    format_size(nm.size)
                 )));
             }
         }
 
-        if !issues.docker_images.is_empty() {
+        if !issues.dependency_dirs.is_empty() {
+            let total_size: u64 = issues.dependency_dirs.iter().map(|d| d.size).sum();
             items.push(ListItem::new(format!(
-                "• Docker images: {} found",
-                issues.docker_images.len()
-            )).style(Style::default().fg(Color::Yellow)));
+                "• CocoaPods/Carthage/SwiftPM: {} in {} dirs",
+                format_size(total_size),
+                issues.dependency_dirs.len()
+            )).style(Style::default().fg(Color::Red)));
+
+            for (i, dir) in issues.dependency_dirs.iter().take(3).enumerate() {
+                items.push(ListItem::new(format!(
+                    "  {}. {} ({})",
+                    i + 1,
+                    dir.path.display(),
+                    format_size(dir.size)
+                )));
+            }
+        }
+
+        if issues.runtimes.is_empty() {
+            items.push(ListItem::new("• Containers: no Docker, Podman or nerdctl runtime found")
+                .style(Style::default().fg(Color::Gray)));
+        }
+
+        for entry in &issues.runtimes {
+            let name = entry.runtime.name;
+            let usage = &entry.usage;
+            if let Some(err) = &entry.error {
+                items.push(ListItem::new(format!("• {name}: {err}")).style(Style::default().fg(Color::Red)));
+                continue;
+            }
+
+            if !usage.images.is_empty() {
+                let order = match app.image_order {
+                    ImageOrder::Size => "largest",
+                    ImageOrder::Age => "oldest",
+                };
+                items.push(ListItem::new(format!(
+                    "• {name} images: {} found, {} reclaimable ({order} first, [s] to sort)",
+                    usage.images.len(),
+                    format_size(usage.reclaimable_images())
+                )).style(Style::default().fg(Color::Yellow)));
             
-            for img in issues.docker_images.iter().take(3) {
+                for img in usage.sorted_images(app.image_order).into_iter().take(3) {
+                    items.push(ListItem::new(format!(
+                        "  - {} ({}, {} unique)",
+                        img.name(),
+                        format_size(img.size),
+                        format_size(img.unique_size())
+                    )));
+                }
+            }
+
+            let containers: Vec<_> = usage.stopped_containers().collect();
+            if !containers.is_empty() {
+                items.push(ListItem::new(format!(
+                    "• {name} containers: {} stopped ({})",
+                    containers.len(),
+                    format_size(containers.iter().map(|c| c.size).sum())
+                )).style(Style::default().fg(Color::Yellow)));
+
+                for container in containers.iter().take(3) {
+                    items.push(ListItem::new(format!(
+                        "  - {} ({}, {})",
+                        container.name,
+                        container.image,
+                        format_size(container.size)
+                    )));
+                }
+            }
+
+            let volumes: Vec<_> = usage.unused_volumes().collect();
+            if !volumes.is_empty() {
+                items.push(ListItem::new(format!(
+                    "• {name} volumes: {} unused ({})",
+                    volumes.len(),
+                    format_size(volumes.iter().map(|v| v.size).sum())
+                )).style(Style::default().fg(Color::Yellow)));
+
+                for volume in volumes.iter().take(3) {
+                    items.push(ListItem::new(format!(
+                        "  - {} ({})",
+                        volume.name,
+                        format_size(volume.size)
+                    )));
+                }
+            }
+
+            if !usage.build_cache.is_empty() {
+                let reclaimable: u64 = usage.build_cache.iter().filter(|r| !r.in_use).map(|r| r.size).sum();
+                items.push(ListItem::new(format!(
+                    "• {name} build cache: {} records, {} reclaimable",
+                    usage.build_cache.len(),
+                    format_size(reclaimable)
+                )).style(Style::default().fg(Color::Yellow)));
+
+                let mut records: Vec<_> = usage.build_cache.iter().collect();
+                records.sort_by_key(|r| std::cmp::Reverse(r.size));
+                for record in records.into_iter().take(3) {
+                    items.push(ListItem::new(format!(
+                        "  - {} ({}, {})",
+                        record.description,
+                        format_size(record.size),
+                        format_last_used(record.last_used)
+                    )));
+                }
+            }
+
+            let networks = usage.unused_networks();
+            if !networks.is_empty() {
+                items.push(ListItem::new(format!(
+                    "• {name} networks: {} unused ({})",
+                    networks.len(),
+                    networks.join(", ")
+                )).style(Style::default().fg(Color::Yellow)));
+            }
+
+            let projects = compose::projects(usage);
+            if !projects.is_empty() {
+                items.push(ListItem::new(format!(
+                    "• {name} Compose projects: {}",
+                    projects.len()
+                )).style(Style::default().fg(Color::Yellow)));
+                for project in projects.iter().take(3) {
+                    items.push(ListItem::new(format!(
+                        "    {} ({}{})",
+                        project.name,
+                        format_size(project.size()),
+                        if project.is_running() { ", running" } else { "" }
+                    )));
+                }
+            }
+        }
+
+        for category in &issues.categories {
+            items.push(ListItem::new(format!(
+                "• {}: {} in {} items",
+                category.name,
+                format_size(category.total_size()),
+                category.findings.len()
+            )).style(Style::default().fg(Color::Magenta)));
+
+            if let Some(note) = &category.note {
+                items.push(ListItem::new(format!("  {note}")).style(Style::default().fg(Color::Cyan)));
+            }
+
+            for finding in category.findings.iter().take(3) {
+                items.push(ListItem::new(format!(
+                    "  - {} ({}, {})",
+                    finding.label,
+                    format_size(finding.size),
+                    format_last_used(finding.last_used)
+                )));
+            }
+        }
+
+        if let Some(brew) = &issues.homebrew {
+            items.push(ListItem::new(format!(
+                "• Homebrew: {} reclaimable in {} items",
+                format_size(brew.reclaimable),
+                brew.removable.len()
+            )).style(Style::default().fg(Color::Yellow)));
+            items.push(ListItem::new(format!(
+                "  - download cache {}",
+                format_size(brew.cache_size)
+            )));
+
+            for (formula, version, size) in &brew.old_kegs {
+                items.push(ListItem::new(format!(
+                    "  - old version: {formula} {version} ({})",
+                    format_size(*size)
+                )));
+            }
+        }
+
+        if let Some(nix) = &issues.nix {
+            items.push(ListItem::new(format!(
+                "• Nix: {} dead in {} store paths, {} profile generations",
+                format_size(nix.dead_bytes),
+                nix.dead_paths,
+                nix.generations.len()
+            )).style(Style::default().fg(Color::Yellow)));
+        }
+
+        if !issues.app_caches.is_empty() {
+            let total: u64 = issues.app_caches.iter().map(|cache| cache.size()).sum();
+            items.push(ListItem::new(format!(
+                "• App caches: {} in {} apps",
+                format_size(total),
+                issues.app_caches.len()
+            )).style(Style::default().fg(Color::Yellow)));
+
+            for cache in issues.app_caches.iter().take(3) {
+                let running = if app.is_app_running(cache) { " - running, close it to clean" } else { "" };
                 items.push(ListItem::new(format!(
-                    "  - {} ({})",
-                    img.name,
-                    img.size
+                    "  - {} ({}){}",
+                    cache.app,
+                    format_size(cache.size()),
+                    running
                 )));
             }
         }
 
+        for cache in &issues.system_caches {
+            items.push(ListItem::new(format!(
+                "• {}: {}",
+                cache.name,
+                format_size(cache.size)
+            )).style(Style::default().fg(Color::Yellow)));
+        }
+
         if !issues.top_processes.is_empty() {
             items.push(ListItem::new("• Top processes by resource usage:").style(Style::default().fg(Color::Cyan)));
             
             for proc in issues.top_processes.iter().take(3) {
                 items.push(ListItem::new(format!(
-                    "  - {} (CPU: {:.1}%, RAM: {:.1}GB)",
+                    "  - {} (CPU: {:.1}%, RAM: {})",
                     proc.name,
                     proc.cpu,
-                    bytes_to_gb(proc.memory)
+                    format_size(proc.memory)
                 )));
             }
         }
@@ -695,8 +2429,17 @@ fn render_issues(f: &mut Frame, app: &App, area: Rect) {
     f.render_widget(list, area);
 }
 
-fn render_help(f: &mut Frame, area: Rect) {
-    let help_text = Paragraph::new("[c] Clean  [q] Quit")
+fn render_help(f: &mut Frame, app: &App, area: Rect) {
+    let keys = if app.dry_run {
+        "[c] Clean  [d] Dry run: on  [r] Results  [q] Quit"
+    } else {
+        "[c] Clean  [d] Dry run: off  [r] Results  [q] Quit"
+    };
+    let text = match app.history.last() {
+        Some(result) => format!("{keys}  |  {}", result.summary()),
+        None => keys.to_string(),
+    };
+    let help_text = Paragraph::new(text)
         .style(Style::default().fg(Color::Gray))
         .block(Block::default().borders(Borders::ALL));
     f.render_widget(help_text, area);
@@ -723,6 +2466,134 @@ fn get_gauge_style(percent: f64) -> Style {
     Style::default().fg(color)
 }
 
-fn bytes_to_gb(bytes: u64) -> f64 {
-    bytes as f64 / 1_073_741_824.0
+fn format_last_used(last_used: Option<SystemTime>) -> String {
+    match last_used {
+        Some(time) => format!("used {}", format_age(time)),
+        None => "last use unknown".to_string(),
+    }
+}
+
+fn format_age(time: SystemTime) -> String {
+    let days = time.elapsed().map(|e| e.as_secs() / 86_400).unwrap_or(0);
+    match days {
+        0 => "today".to_string(),
+        1..=59 => format!("{days}d ago"),
+        60..=729 => format!("{}mo ago", days / 30),
+        _ => format!("{}y ago", days / 365),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(path: &str) -> Finding {
+        let mut finding = Finding::from_path(path, Path::new("/nonexistent"));
+        finding.path = PathBuf::from(path);
+        finding
+    }
+
+    fn paths(app: &App) -> Vec<&str> {
+        let mut paths: Vec<&str> = app.checked.iter().filter_map(|path| path.to_str()).collect();
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn ticks_toggles_and_inverts_checklist_items() {
+        let cache = |app, processes, path| AppCache { app, processes, caches: vec![finding(path)] };
+        let mut app = App::with_issues(IssuesData {
+            app_caches: vec![
+                cache("Chrome", &["google chrome"], "/c/chrome"),
+                cache("Firefox", &["firefox"], "/c/firefox"),
+                cache("Slack", &["slack"], "/c/slack"),
+            ],
+            ..IssuesData::default()
+        });
+        app.running.insert("firefox".to_string());
+        let row = CleanupRow::AppCaches;
+
+        update_checked(&mut app, row, 0, KeyCode::Char('a'));
+        assert_eq!(paths(&app), ["/c/chrome", "/c/slack"]);
+
+        update_checked(&mut app, row, 0, KeyCode::Char(' '));
+        assert_eq!(paths(&app), ["/c/slack"]);
+
+        update_checked(&mut app, row, 1, KeyCode::Char(' '));
+        assert_eq!(paths(&app), ["/c/slack"]);
+
+        update_checked(&mut app, row, 0, KeyCode::Char('i'));
+        assert_eq!(paths(&app), ["/c/chrome"]);
+
+        update_checked(&mut app, row, 0, KeyCode::Char('u'));
+        assert!(app.checked.is_empty());
+    }
+
+    #[test]
+    fn asks_for_yes_only_for_large_permanent_removals() {
+        let needs_typing = |action: PendingAction, bytes: u64, deletion: Option<Deletion>| {
+            let mut app = App::with_issues(IssuesData::default());
+            let trashed = if deletion == Some(Deletion::Trash) { bytes } else { 0 };
+            let plan = CleanupPlan { action: "Clean".to_string(), items: Vec::new(), bytes, deletion, trashed };
+            confirm(&mut app, action, plan);
+            match app.mode {
+                AppMode::Confirm { typed, .. } => typed.is_some(),
+                _ => panic!("no confirmation dialog"),
+            }
+        };
+        let cleanup = PendingAction::Cleanup(CleanupRow::NodeModules);
+
+        assert!(needs_typing(cleanup.clone(), GB + 1, Some(Deletion::Permanent)));
+        assert!(!needs_typing(cleanup.clone(), GB, Some(Deletion::Permanent)));
+        assert!(!needs_typing(cleanup.clone(), 5 * GB, Some(Deletion::Trash)));
+        assert!(needs_typing(cleanup, 5 * GB, None));
+        assert!(!needs_typing(PendingAction::Kill { pid: 1 }, 5 * GB, None));
+    }
+
+    #[test]
+    fn asks_for_yes_when_a_mixed_plan_removes_a_large_part_for_good() {
+        let mut vm = finding("/vms/Windows");
+        vm.size = 20 * GB;
+        vm.removal = Removal::Command { args: vec!["VBoxManage".to_string()], needs_root: false };
+        let mut utm = finding("/utm/Debian.utm");
+        utm.size = 300 * 1024 * 1024;
+        let mut app = App::with_issues(IssuesData {
+            categories: vec![Category::new("Virtual machines", vec![vm, utm])],
+            ..IssuesData::default()
+        });
+
+        let row = CleanupRow::Category(0);
+        let plan = dry_run::plan(&app, row).unwrap();
+        confirm(&mut app, PendingAction::Cleanup(row), plan);
+        match app.mode {
+            AppMode::Confirm { typed, plan, .. } => {
+                assert!(typed.is_some());
+                assert_eq!((plan.trashed, plan.permanent_bytes()), (300 * 1024 * 1024, 20 * GB));
+            },
+            _ => panic!("no confirmation dialog"),
+        }
+    }
+
+    #[test]
+    fn scrolls_the_cleanup_menu_to_the_selected_row() {
+        let runtime = |name| RuntimeUsage {
+            runtime: Runtime::api(name, docker::DockerClient::new("/nonexistent.sock")),
+            usage: docker::DockerUsage::default(),
+            error: None,
+        };
+        let app = App::with_issues(IssuesData {
+            runtimes: vec![runtime("Docker"), runtime("Podman")],
+            ..IssuesData::default()
+        });
+        let last = cleanup_menu_len(&app) - 1;
+        let mut terminal = Terminal::new(ratatui::backend::TestBackend::new(100, 24)).unwrap();
+
+        terminal.draw(|f| render_cleanup_menu(f, &app, last)).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let screen: String = buffer.content().iter().map(|cell| cell.symbol()).collect();
+        assert!(screen.contains("Kill heavy processes (free RAM)"));
+        assert!(screen.contains("[Enter] Execute"));
+        assert!(!screen.contains("Clean 0 node_modules"));
+    }
 }
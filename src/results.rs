@@ -0,0 +1,103 @@
+use crate::size::format_size;
+use std::{fmt::Display, time::SystemTime};
+
+/// What a cleanup or kill actually did, kept in the session history.
+pub struct ActionResult {
+    pub action: String,
+    pub finished: SystemTime,
+    pub succeeded: Vec<String>,
+    /// Items that could not be removed or killed, with the reason.
+    pub failed: Vec<(String, String)>,
+    pub bytes_freed: u64,
+    /// Bytes moved to the trash, which stay on disk until the trash is emptied.
+    pub bytes_trashed: u64,
+    /// Standard error of the external commands the action ran.
+    pub stderr: String,
+}
+
+impl ActionResult {
+    pub fn new(action: impl Into<String>) -> Self {
+        Self {
+            action: action.into(),
+            finished: SystemTime::now(),
+            succeeded: Vec::new(),
+            failed: Vec::new(),
+            bytes_freed: 0,
+            bytes_trashed: 0,
+            stderr: String::new(),
+        }
+    }
+
+    pub fn succeeded(&mut self, item: impl Into<String>, bytes: u64) {
+        self.succeeded.push(item.into());
+        self.bytes_freed += bytes;
+    }
+
+    pub fn trashed(&mut self, item: impl Into<String>, bytes: u64) {
+        self.succeeded.push(item.into());
+        self.bytes_trashed += bytes;
+    }
+
+    pub fn failed(&mut self, item: impl Into<String>, err: impl Display) {
+        self.failed.push((item.into(), err.to_string()));
+    }
+
+    pub fn add_stderr(&mut self, stderr: &str) {
+        let stderr = stderr.trim();
+        if !stderr.is_empty() {
+            self.stderr.push_str(stderr);
+            self.stderr.push('\n');
+        }
+    }
+
+    /// `2.5MB freed`, plus what went to the trash when anything did.
+    pub fn space(&self) -> String {
+        let freed = format!("{} freed", format_size(self.bytes_freed));
+        if self.bytes_trashed == 0 {
+            return freed;
+        }
+        format!("{freed}, {} moved to trash", format_size(self.bytes_trashed))
+    }
+
+    /// One line for the status bar.
+    pub fn summary(&self) -> String {
+        let done = format!("{}: {} done, {}", self.action, self.succeeded.len(), self.space());
+        match self.failed.first() {
+            None => done,
+            Some((item, err)) => format!("{done}, {} failed ({item}: {err})", self.failed.len()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarises_successes_and_failures() {
+        let mut result = ActionResult::new("Clean node_modules");
+        result.succeeded("/home/me/a/node_modules", 2_097_152);
+        result.succeeded("/home/me/b/node_modules", 524_288);
+        assert_eq!(result.summary(), "Clean node_modules: 2 done, 2.5MB freed");
+
+        result.failed("/home/me/c/node_modules", "Permission denied (os error 13)");
+        result.add_stderr("  \n");
+        result.add_stderr("warning: something\n");
+        assert_eq!(
+            result.summary(),
+            "Clean node_modules: 2 done, 2.5MB freed, 1 failed (/home/me/c/node_modules: Permission denied (os error 13))"
+        );
+        assert_eq!(result.stderr, "warning: something\n");
+    }
+
+    #[test]
+    fn counts_trashed_bytes_apart_from_freed_ones() {
+        let mut result = ActionResult::new("Move to the trash: node_modules");
+        result.trashed("/home/me/a/node_modules", 2_097_152);
+        assert_eq!(
+            result.summary(),
+            "Move to the trash: node_modules: 1 done, 0B freed, 2.0MB moved to trash"
+        );
+        assert_eq!(result.bytes_freed, 0);
+    }
+}
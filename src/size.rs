@@ -0,0 +1,40 @@
+/// Bytes in a gigabyte. Sizes are shown in binary units throughout, labelled `KB`, `MB` and
+/// `GB` as macOS Finder and `du -h` do.
+pub const GB: u64 = 1024 * 1024 * 1024;
+
+/// Gigabytes as a fraction, for the disk, memory and swap gauges.
+pub fn bytes_to_gb(bytes: u64) -> f64 {
+    bytes as f64 / GB as f64
+}
+
+/// `512B`, `1.5MB`, `2.3GB`: the one formatter used by menus, dialogs, results and dry runs.
+pub fn format_size(bytes: u64) -> String {
+    let units = ["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < units.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes}B")
+    } else {
+        format!("{size:.1}{}", units[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_sizes_in_binary_units() {
+        assert_eq!(format_size(0), "0B");
+        assert_eq!(format_size(1023), "1023B");
+        assert_eq!(format_size(1536), "1.5KB");
+        assert_eq!(format_size(2_500_000), "2.4MB");
+        assert_eq!(format_size(GB), "1.0GB");
+        assert_eq!(format_size(3 * GB / 2), "1.5GB");
+        assert_eq!(format_size(2048 * GB), "2.0TB");
+    }
+}
@@ -0,0 +1,219 @@
+use chrono::Local;
+use std::{
+    env,
+    fs::{self, DirBuilder, OpenOptions},
+    io::{self, Write},
+    os::unix::{
+        ffi::OsStrExt,
+        fs::{DirBuilderExt, MetadataExt},
+    },
+    path::{Path, PathBuf},
+};
+
+/// What happens to files and directories a cleanup removes.
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum Deletion {
+    /// Moved to the trash, so a mistaken cleanup can be undone from the file manager.
+    #[default]
+    Trash,
+    Permanent,
+}
+
+impl Deletion {
+    pub fn toggle(self) -> Self {
+        match self {
+            Self::Trash => Self::Permanent,
+            Self::Permanent => Self::Trash,
+        }
+    }
+}
+
+/// Removes `path` the way `deletion` asks. Symlinks are removed, never followed.
+pub fn delete(path: &Path, deletion: Deletion) -> io::Result<()> {
+    match deletion {
+        Deletion::Trash => trash(path).map(drop),
+        Deletion::Permanent if fs::symlink_metadata(path)?.is_dir() => fs::remove_dir_all(path),
+        Deletion::Permanent => fs::remove_file(path),
+    }
+}
+
+/// Moves `path` into the trash of the volume it lives on and returns where it ended up:
+/// `~/.Trash` or `<volume>/.Trashes/<uid>` on macOS, and the freedesktop.org home or
+/// per-volume trash elsewhere.
+pub fn trash(path: &Path) -> io::Result<PathBuf> {
+    let path = std::path::absolute(path)?;
+    let home = PathBuf::from(env::var_os("HOME").ok_or_else(|| io::Error::other("HOME is not set"))?);
+
+    let result = if cfg!(target_os = "macos") {
+        trash_macos(&path, &home)
+    } else {
+        trash_freedesktop(&path, &home)
+    };
+    result.map_err(|err| io::Error::new(err.kind(), format!("could not move {} to the trash: {err}", path.display())))
+}
+
+fn trash_macos(path: &Path, home: &Path) -> io::Result<PathBuf> {
+    let dev = fs::symlink_metadata(path)?.dev();
+    let trash = if device(home)? == dev {
+        home.join(".Trash")
+    } else {
+        top_dir(path, dev).join(".Trashes").join(fs::metadata(home)?.uid().to_string())
+    };
+    DirBuilder::new().recursive(true).mode(0o700).create(&trash)?;
+
+    let name = path.file_name().ok_or_else(|| io::Error::other("path has no file name"))?;
+    let name = name.to_string_lossy();
+    let target = (1..)
+        .map(|n| if n == 1 { trash.join(&*name) } else { trash.join(format!("{name} {n}")) })
+        .find(|target| fs::symlink_metadata(target).is_err())
+        .unwrap();
+    fs::rename(path, &target)?;
+    Ok(target)
+}
+
+/// Follows the freedesktop.org Trash specification: files on the home volume go to
+/// `$XDG_DATA_HOME/Trash`, others to `<top>/.Trash/<uid>` when the administrator created a
+/// sticky `.Trash` there, or to `<top>/.Trash-<uid>` otherwise.
+fn trash_freedesktop(path: &Path, home: &Path) -> io::Result<PathBuf> {
+    let dev = fs::symlink_metadata(path)?.dev();
+    let data_home = env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .unwrap_or_else(|| home.join(".local/share"));
+    let home_trash = data_home.join("Trash");
+    if device(&home_trash)? == dev {
+        return move_into(&home_trash, path, path);
+    }
+
+    let top = top_dir(path, dev);
+    let relative = path.strip_prefix(&top).unwrap_or(path);
+    let uid = fs::metadata(home)?.uid();
+
+    let shared = top.join(".Trash");
+    if let Ok(meta) = fs::symlink_metadata(&shared)
+        && meta.is_dir()
+        && meta.mode() & 0o1000 != 0
+        && let Ok(target) = move_into(&shared.join(uid.to_string()), path, relative)
+    {
+        return Ok(target);
+    }
+    move_into(&top.join(format!(".Trash-{uid}")), path, relative)
+}
+
+/// Moves `path` into `trash/files`, next to a `trash/info/<name>.trashinfo` recording
+/// `original` and the deletion time. Clashing names get a `.2`, `.3`, ... suffix; the info
+/// file is created first and exclusively, so two processes never claim the same name.
+fn move_into(trash: &Path, path: &Path, original: &Path) -> io::Result<PathBuf> {
+    let files = trash.join("files");
+    let info = trash.join("info");
+    DirBuilder::new().recursive(true).mode(0o700).create(&files)?;
+    DirBuilder::new().recursive(true).mode(0o700).create(&info)?;
+
+    let name = path.file_name().ok_or_else(|| io::Error::other("path has no file name"))?;
+    let name = name.to_string_lossy();
+    for n in 1.. {
+        let candidate = if n == 1 { name.to_string() } else { format!("{name}.{n}") };
+        let target = files.join(&candidate);
+        if fs::symlink_metadata(&target).is_ok() {
+            continue;
+        }
+
+        let info_file = info.join(format!("{candidate}.trashinfo"));
+        let mut file = match OpenOptions::new().write(true).create_new(true).open(&info_file) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(err) => return Err(err),
+        };
+
+        let contents = format!(
+            "[Trash Info]\nPath={}\nDeletionDate={}\n",
+            encode(original),
+            Local::now().format("%Y-%m-%dT%H:%M:%S")
+        );
+        if let Err(err) = file.write_all(contents.as_bytes()).and_then(|()| fs::rename(path, &target)) {
+            let _ = fs::remove_file(&info_file);
+            return Err(err);
+        }
+        return Ok(target);
+    }
+    unreachable!()
+}
+
+/// The device of `path`, or of its nearest existing ancestor.
+fn device(path: &Path) -> io::Result<u64> {
+    path.ancestors()
+        .find_map(|dir| fs::metadata(dir).ok())
+        .map(|meta| meta.dev())
+        .ok_or_else(|| io::Error::other(format!("{} does not exist", path.display())))
+}
+
+/// The mount point `path` lives under: its highest ancestor on the same device.
+fn top_dir(path: &Path, dev: u64) -> PathBuf {
+    let mut top = path;
+    while let Some(parent) = top.parent()
+        && fs::metadata(parent).is_ok_and(|meta| meta.dev() == dev)
+    {
+        top = parent;
+    }
+    top.to_path_buf()
+}
+
+/// Percent-encodes a path for the `Path=` key, keeping `/` and unreserved characters.
+fn encode(path: &Path) -> String {
+    path.as_os_str()
+        .as_bytes()
+        .iter()
+        .map(|&byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                (byte as char).to_string()
+            },
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moves_into_the_trash_with_an_info_file() {
+        let dir = env::temp_dir().join(format!("macmon-trash-{}", std::process::id()));
+        let trash = dir.join("Trash");
+        fs::create_dir_all(dir.join("my project/node_modules/left-pad")).unwrap();
+        fs::write(dir.join("my project/node_modules/left-pad/index.js"), "").unwrap();
+        fs::create_dir_all(dir.join("other/node_modules")).unwrap();
+
+        let first = dir.join("my project/node_modules");
+        let target = move_into(&trash, &first, &first).unwrap();
+
+        assert_eq!(target, trash.join("files/node_modules"));
+        assert!(target.join("left-pad/index.js").is_file());
+        assert!(!first.exists());
+        let info = fs::read_to_string(trash.join("info/node_modules.trashinfo")).unwrap();
+        assert!(info.starts_with("[Trash Info]\n"));
+        assert!(info.contains(&format!("Path={}/my%20project/node_modules\n", encode(&dir))));
+        assert!(info.contains("DeletionDate="));
+
+        let second = dir.join("other/node_modules");
+        let target = move_into(&trash, &second, Path::new("other/node_modules")).unwrap();
+
+        assert_eq!(target, trash.join("files/node_modules.2"));
+        let info = fs::read_to_string(trash.join("info/node_modules.2.trashinfo")).unwrap();
+        assert!(info.contains("Path=other/node_modules\n"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn leaves_no_info_file_when_the_move_fails() {
+        let dir = env::temp_dir().join(format!("macmon-trash-missing-{}", std::process::id()));
+        let trash = dir.join("Trash");
+        let missing = dir.join("missing");
+
+        assert!(move_into(&trash, &missing, &missing).is_err());
+        assert_eq!(fs::read_dir(trash.join("info")).unwrap().count(), 0);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}